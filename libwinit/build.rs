@@ -19,5 +19,17 @@ fn main() {
         x11_platform: { all(free_unix, not(wasm), not(redox)) },
         wayland_platform: { all(free_unix, not(wasm), not(redox)) },
         orbital_platform: { redox },
+
+        // Platforms that support `EventLoopExtRunReturn`.
+        run_return_platform: { any(
+            target_os = "windows",
+            target_os = "macos",
+            target_os = "android",
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        ) },
     }
 }
@@ -0,0 +1,313 @@
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use value_box::{ReturnBoxerResult, ValueBox, ValueBoxPointer};
+use winit::window::Window;
+
+use crate::{Result, WinitError};
+
+/// A cursor image given as RGBA8 pixels with a hotspot relative to its top-left corner
+#[derive(Debug, Clone)]
+pub struct CursorImage {
+    rgba: Vec<u8>,
+    width: u16,
+    height: u16,
+    hotspot_x: u16,
+    hotspot_y: u16,
+}
+
+impl CursorImage {
+    pub fn from_rgba(
+        rgba: Vec<u8>,
+        width: u16,
+        height: u16,
+        hotspot_x: u16,
+        hotspot_y: u16,
+    ) -> Result<Self> {
+        let expected_length = width as usize * height as usize * 4;
+        if width == 0 || height == 0 {
+            return Err(WinitError::InvalidCursorImage(
+                "The cursor image is empty".to_string(),
+            ));
+        }
+        if rgba.len() != expected_length {
+            return Err(WinitError::InvalidCursorImage(format!(
+                "Expected {} bytes for a {}x{} RGBA image, got {}",
+                expected_length,
+                width,
+                height,
+                rgba.len()
+            )));
+        }
+        if hotspot_x >= width || hotspot_y >= height {
+            return Err(WinitError::InvalidCursorImage(format!(
+                "The hotspot {}@{} is outside of the {}x{} image",
+                hotspot_x, hotspot_y, width, height
+            )));
+        }
+
+        Ok(Self {
+            rgba,
+            width,
+            height,
+            hotspot_x,
+            hotspot_y,
+        })
+    }
+}
+
+/// A custom cursor with image variants for different scale factors.
+/// Native cursors are created lazily per display and reused when the cursor is set again.
+#[derive(Debug, Clone)]
+pub struct CustomCursor {
+    data: Arc<CustomCursorData>,
+}
+
+#[derive(Debug)]
+struct CustomCursorData {
+    /// Sorted by the scale factor
+    images: Mutex<Vec<(f64, CursorImage)>>,
+    #[cfg(x11_platform)]
+    x11_cursors: Mutex<Vec<x11::LoadedCursor>>,
+}
+
+impl CustomCursor {
+    /// Create a cursor with an image for the scale factor of 1.0
+    pub fn new(image: CursorImage) -> Self {
+        Self {
+            data: Arc::new(CustomCursorData {
+                images: Mutex::new(vec![(1.0, image)]),
+                #[cfg(x11_platform)]
+                x11_cursors: Default::default(),
+            }),
+        }
+    }
+
+    /// Add or replace an image used for windows with a given scale factor
+    pub fn add_variant(&self, scale_factor: f64, image: CursorImage) -> Result<()> {
+        if !scale_factor.is_normal() || scale_factor < 0.0 {
+            return Err(WinitError::InvalidCursorImage(format!(
+                "Invalid scale factor {}",
+                scale_factor
+            )));
+        }
+
+        let mut images = self.data.images.lock();
+        images.retain(|(each_scale_factor, _)| *each_scale_factor != scale_factor);
+        images.push((scale_factor, image));
+        images.sort_by(|a, b| a.0.total_cmp(&b.0));
+        drop(images);
+
+        // variants may now be picked differently
+        #[cfg(x11_platform)]
+        x11::free_cursors(std::mem::take(&mut *self.data.x11_cursors.lock()));
+        Ok(())
+    }
+
+    /// Pick the smallest image that is not smaller than needed for a given scale factor,
+    /// or the largest one if there is no such image
+    fn image_index(&self, scale_factor: f64) -> usize {
+        let images = self.data.images.lock();
+        images
+            .iter()
+            .position(|(each_scale_factor, _)| *each_scale_factor >= scale_factor)
+            .unwrap_or(images.len() - 1)
+    }
+
+    /// Show the cursor over the window. Must be called from the event loop thread
+    pub fn apply(&self, window: &Window) -> Result<()> {
+        #[cfg(x11_platform)]
+        if let Some((display, xlib_window)) = crate::x11::xlib_window(window) {
+            return x11::apply(self, display, xlib_window, window.scale_factor());
+        }
+
+        let _ = window;
+        Err(WinitError::CustomCursorNotSupported)
+    }
+}
+
+/// winit 0.28 can't create cursors from images, so on X11 we do it with Xcursor directly
+#[cfg(x11_platform)]
+mod x11 {
+    use std::ffi::{c_int, c_ulong};
+
+    use x11_dl::xcursor::Xcursor;
+    use x11_dl::xlib::Display;
+
+    use super::CustomCursor;
+    use crate::x11::{xcursor, xlib};
+    use crate::{Result, WinitError};
+
+    #[derive(Debug)]
+    pub(super) struct LoadedCursor {
+        display: usize,
+        image_index: usize,
+        cursor: c_ulong,
+    }
+
+    pub(super) fn apply(
+        cursor: &CustomCursor,
+        display: *mut Display,
+        window: c_ulong,
+        scale_factor: f64,
+    ) -> Result<()> {
+        let xlib = xlib()?;
+        let xcursor = xcursor()?;
+        let image_index = cursor.image_index(scale_factor);
+
+        let mut loaded_cursors = cursor.data.x11_cursors.lock();
+        let x11_cursor = match loaded_cursors
+            .iter()
+            .find(|each| each.display == display as usize && each.image_index == image_index)
+        {
+            Some(loaded_cursor) => loaded_cursor.cursor,
+            None => {
+                let x11_cursor = load_cursor(xcursor, cursor, display, image_index)?;
+                loaded_cursors.push(LoadedCursor {
+                    display: display as usize,
+                    image_index,
+                    cursor: x11_cursor,
+                });
+                x11_cursor
+            }
+        };
+
+        unsafe {
+            (xlib.XDefineCursor)(display, window, x11_cursor);
+            (xlib.XFlush)(display);
+        }
+        Ok(())
+    }
+
+    fn load_cursor(
+        xcursor: &Xcursor,
+        cursor: &CustomCursor,
+        display: *mut Display,
+        image_index: usize,
+    ) -> Result<c_ulong> {
+        let images = cursor.data.images.lock();
+        let image = &images[image_index].1;
+
+        unsafe {
+            let x_image = (xcursor.XcursorImageCreate)(image.width as c_int, image.height as c_int);
+            if x_image.is_null() {
+                return Err(WinitError::CustomCursorNotSupported);
+            }
+            (*x_image).xhot = image.hotspot_x as u32;
+            (*x_image).yhot = image.hotspot_y as u32;
+            (*x_image).delay = 0;
+
+            // Xcursor expects premultiplied ARGB
+            let pixels = std::slice::from_raw_parts_mut(
+                (*x_image).pixels,
+                image.width as usize * image.height as usize,
+            );
+            for (pixel, rgba) in pixels.iter_mut().zip(image.rgba.chunks_exact(4)) {
+                let alpha = rgba[3] as u32;
+                let premultiply = |channel: u8| (channel as u32 * alpha + 127) / 255;
+                *pixel = (alpha << 24)
+                    | (premultiply(rgba[0]) << 16)
+                    | (premultiply(rgba[1]) << 8)
+                    | premultiply(rgba[2]);
+            }
+
+            let x11_cursor = (xcursor.XcursorImageLoadCursor)(display, x_image);
+            (xcursor.XcursorImageDestroy)(x_image);
+            if x11_cursor == 0 {
+                return Err(WinitError::InvalidCursorImage(format!(
+                    "Xcursor could not create a cursor from the {}x{} image",
+                    image.width, image.height
+                )));
+            }
+            Ok(x11_cursor)
+        }
+    }
+
+    /// Cursors of a closed display are already gone together with its connection
+    pub(super) fn free_cursors(loaded_cursors: Vec<LoadedCursor>) {
+        if let Ok(xlib) = xlib() {
+            for loaded_cursor in loaded_cursors {
+                crate::x11::with_open_display(loaded_cursor.display as *mut Display, |display| {
+                    unsafe { (xlib.XFreeCursor)(display, loaded_cursor.cursor) };
+                });
+            }
+        }
+    }
+
+    impl Drop for super::CustomCursorData {
+        fn drop(&mut self) {
+            free_cursors(std::mem::take(self.x11_cursors.get_mut()));
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////
+///////////////////////////// C U S T O M    C U R S O R //////////////////////////////
+///////////////////////////////////////////////////////////////////////////////////////
+
+fn cursor_image_from_raw(
+    rgba: *const u8,
+    length: usize,
+    width: u16,
+    height: u16,
+    hotspot_x: u16,
+    hotspot_y: u16,
+) -> Result<CursorImage> {
+    if rgba.is_null() {
+        return Err(WinitError::InvalidCursorImage(
+            "The pixel buffer is null".to_string(),
+        ));
+    }
+    let rgba = unsafe { std::slice::from_raw_parts(rgba, length) }.to_vec();
+    CursorImage::from_rgba(rgba, width, height, hotspot_x, hotspot_y)
+}
+
+/// Create a custom cursor from RGBA8 pixels used for the scale factor of 1.0.
+/// Return null if the image is not valid.
+/// # Safety
+/// `rgba` must point to at least `length` bytes
+#[no_mangle]
+pub unsafe extern "C" fn winit_custom_cursor_new(
+    rgba: *const u8,
+    length: usize,
+    width: u16,
+    height: u16,
+    hotspot_x: u16,
+    hotspot_y: u16,
+) -> *mut ValueBox<CustomCursor> {
+    cursor_image_from_raw(rgba, length, width, height, hotspot_x, hotspot_y)
+        .map(|image| ValueBox::new(CustomCursor::new(image)).into_raw())
+        .map_err(|err| err.boxed().into())
+        .or_log(std::ptr::null_mut())
+}
+
+/// Add an image that is used for windows with a given scale factor.
+/// Return false if the image is not valid.
+/// # Safety
+/// `rgba` must point to at least `length` bytes
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn winit_custom_cursor_add_variant(
+    cursor: *mut ValueBox<CustomCursor>,
+    scale_factor: f64,
+    rgba: *const u8,
+    length: usize,
+    width: u16,
+    height: u16,
+    hotspot_x: u16,
+    hotspot_y: u16,
+) -> bool {
+    cursor
+        .with_ref(|cursor| {
+            cursor_image_from_raw(rgba, length, width, height, hotspot_x, hotspot_y)
+                .and_then(|image| cursor.add_variant(scale_factor, image))
+                .map_err(|err| err.boxed().into())
+        })
+        .map(|_| true)
+        .or_log(false)
+}
+
+#[no_mangle]
+pub extern "C" fn winit_custom_cursor_drop(ptr: *mut ValueBox<CustomCursor>) {
+    ptr.release();
+}
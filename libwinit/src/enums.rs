@@ -1,10 +1,26 @@
-use winit::window::CursorIcon;
+use winit::window::{
+    CursorGrabMode, CursorIcon, Fullscreen, ResizeDirection, Theme, UserAttentionType, WindowLevel,
+};
 
 #[derive(Debug, Copy, Clone)]
 #[repr(u32)]
 pub enum WinitUserEvent {
     /// The virtual machine sends
     WakeUp,
+    /// Wakes up the event loop to process a pending exit request. Not delivered to the host
+    Exit,
+    /// Wakes up the event loop to run dispatched callbacks. Not delivered to the host
+    Dispatch,
+}
+
+impl WinitUserEvent {
+    /// Internal events are used by the library itself to wake up the event loop
+    pub fn is_internal(&self) -> bool {
+        match self {
+            Self::WakeUp => false,
+            Self::Exit | Self::Dispatch => true,
+        }
+    }
 }
 
 impl Default for WinitUserEvent {
@@ -110,3 +126,222 @@ impl From<WinitCursorIcon> for CursorIcon {
         }
     }
 }
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u32)]
+pub enum WinitTheme {
+    /// The theme is not known or not supported by the platform
+    Unknown,
+    Light,
+    Dark,
+}
+
+impl From<Option<Theme>> for WinitTheme {
+    fn from(theme: Option<Theme>) -> Self {
+        match theme {
+            None => WinitTheme::Unknown,
+            Some(Theme::Light) => WinitTheme::Light,
+            Some(Theme::Dark) => WinitTheme::Dark,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u32)]
+pub enum WinitFullscreen {
+    /// The window is not fullscreen
+    Windowed,
+    /// The window covers a monitor without changing its video mode
+    Borderless,
+    /// The window exclusively owns a monitor and sets its video mode
+    Exclusive,
+}
+
+impl From<Option<&Fullscreen>> for WinitFullscreen {
+    fn from(fullscreen: Option<&Fullscreen>) -> Self {
+        match fullscreen {
+            None => WinitFullscreen::Windowed,
+            Some(Fullscreen::Borderless(_)) => WinitFullscreen::Borderless,
+            Some(Fullscreen::Exclusive(_)) => WinitFullscreen::Exclusive,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u32)]
+pub enum WinitWindowLevel {
+    Normal,
+    AlwaysOnTop,
+    AlwaysOnBottom,
+}
+
+impl From<WindowLevel> for WinitWindowLevel {
+    fn from(level: WindowLevel) -> Self {
+        match level {
+            WindowLevel::Normal => WinitWindowLevel::Normal,
+            WindowLevel::AlwaysOnTop => WinitWindowLevel::AlwaysOnTop,
+            WindowLevel::AlwaysOnBottom => WinitWindowLevel::AlwaysOnBottom,
+        }
+    }
+}
+
+impl From<WinitWindowLevel> for WindowLevel {
+    fn from(level: WinitWindowLevel) -> Self {
+        match level {
+            WinitWindowLevel::Normal => WindowLevel::Normal,
+            WinitWindowLevel::AlwaysOnTop => WindowLevel::AlwaysOnTop,
+            WinitWindowLevel::AlwaysOnBottom => WindowLevel::AlwaysOnBottom,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u32)]
+pub enum WinitCursorGrabMode {
+    /// The cursor can freely leave the window
+    None,
+    /// The cursor is confined to the window area
+    Confined,
+    /// The cursor is locked inside of the window area to a certain position
+    Locked,
+}
+
+impl From<CursorGrabMode> for WinitCursorGrabMode {
+    fn from(mode: CursorGrabMode) -> Self {
+        match mode {
+            CursorGrabMode::None => WinitCursorGrabMode::None,
+            CursorGrabMode::Confined => WinitCursorGrabMode::Confined,
+            CursorGrabMode::Locked => WinitCursorGrabMode::Locked,
+        }
+    }
+}
+
+impl From<WinitCursorGrabMode> for CursorGrabMode {
+    fn from(mode: WinitCursorGrabMode) -> Self {
+        match mode {
+            WinitCursorGrabMode::None => CursorGrabMode::None,
+            WinitCursorGrabMode::Confined => CursorGrabMode::Confined,
+            WinitCursorGrabMode::Locked => CursorGrabMode::Locked,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u32)]
+pub enum WinitUserAttention {
+    /// Cancel a previous request
+    None,
+    /// Keep requesting attention until the window is focused
+    Critical,
+    /// Request attention once or until the window is focused, depending on the platform
+    Informational,
+}
+
+impl From<WinitUserAttention> for Option<UserAttentionType> {
+    fn from(attention: WinitUserAttention) -> Self {
+        match attention {
+            WinitUserAttention::None => None,
+            WinitUserAttention::Critical => Some(UserAttentionType::Critical),
+            WinitUserAttention::Informational => Some(UserAttentionType::Informational),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u32)]
+pub enum WinitResizeDirection {
+    East,
+    North,
+    NorthEast,
+    NorthWest,
+    South,
+    SouthEast,
+    SouthWest,
+    West,
+}
+
+impl From<WinitResizeDirection> for ResizeDirection {
+    fn from(direction: WinitResizeDirection) -> Self {
+        match direction {
+            WinitResizeDirection::East => ResizeDirection::East,
+            WinitResizeDirection::North => ResizeDirection::North,
+            WinitResizeDirection::NorthEast => ResizeDirection::NorthEast,
+            WinitResizeDirection::NorthWest => ResizeDirection::NorthWest,
+            WinitResizeDirection::South => ResizeDirection::South,
+            WinitResizeDirection::SouthEast => ResizeDirection::SouthEast,
+            WinitResizeDirection::SouthWest => ResizeDirection::SouthWest,
+            WinitResizeDirection::West => ResizeDirection::West,
+        }
+    }
+}
+
+/// The `_NET_WM_WINDOW_TYPE` of an X11 window
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u32)]
+pub enum WinitX11WindowType {
+    Normal,
+    Desktop,
+    Dock,
+    Toolbar,
+    Menu,
+    Utility,
+    Splash,
+    Dialog,
+    DropdownMenu,
+    PopupMenu,
+    Tooltip,
+    Notification,
+    Combo,
+    Dnd,
+}
+
+#[cfg(x11_platform)]
+impl From<WinitX11WindowType> for winit::platform::x11::XWindowType {
+    fn from(window_type: WinitX11WindowType) -> Self {
+        use winit::platform::x11::XWindowType;
+        match window_type {
+            WinitX11WindowType::Normal => XWindowType::Normal,
+            WinitX11WindowType::Desktop => XWindowType::Desktop,
+            WinitX11WindowType::Dock => XWindowType::Dock,
+            WinitX11WindowType::Toolbar => XWindowType::Toolbar,
+            WinitX11WindowType::Menu => XWindowType::Menu,
+            WinitX11WindowType::Utility => XWindowType::Utility,
+            WinitX11WindowType::Splash => XWindowType::Splash,
+            WinitX11WindowType::Dialog => XWindowType::Dialog,
+            WinitX11WindowType::DropdownMenu => XWindowType::DropdownMenu,
+            WinitX11WindowType::PopupMenu => XWindowType::PopupMenu,
+            WinitX11WindowType::Tooltip => XWindowType::Tooltip,
+            WinitX11WindowType::Notification => XWindowType::Notification,
+            WinitX11WindowType::Combo => XWindowType::Combo,
+            WinitX11WindowType::Dnd => XWindowType::Dnd,
+        }
+    }
+}
+
+/// How a window created with a parent window relates to it
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u32)]
+pub enum WinitParentRelation {
+    /// The window is placed inside of the parent and confined to its client area
+    Child,
+    /// The window is a top-level window that stays above its owner and is minimized with it
+    Owned,
+}
+
+/// The format of a serialized window configuration
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u32)]
+pub enum WinitConfigFormat {
+    Json,
+    Toml,
+}
+
+/// What is wrong with a key of a window configuration
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u32)]
+pub enum WinitConfigProblemKind {
+    /// The document can't be parsed at all
+    Syntax,
+    UnknownKey,
+    InvalidValue,
+}
@@ -3,11 +3,11 @@ use std::sync::PoisonError;
 
 use thiserror::Error;
 use value_box::BoxerError;
-use winit::error::NotSupportedError;
+use winit::error::{ExternalError, NotSupportedError};
 use winit::event_loop::EventLoopClosed;
-use winit::window::WindowId;
+use winit::window::{BadIcon, WindowId};
 
-use crate::WinitUserEvent;
+use crate::{WindowConfigError, WinitUserEvent};
 
 #[derive(Error, Debug)]
 pub enum WinitError {
@@ -17,12 +17,32 @@ pub enum WinitError {
     PoisonError,
     #[error("Event loop is not running")]
     EventLoopNotRunning,
+    #[error("Dispatched callback was dropped before it could run")]
+    DispatchCancelled,
     #[error("Window with id {0:?} not found")]
     WindowNotFound(WindowId),
     #[error("Event loop closed")]
     EventLoopClosed(#[from] EventLoopClosed<WinitUserEvent>),
     #[error("Not supported error")]
     NotSupportedError(#[from] NotSupportedError),
+    #[error("External error")]
+    ExternalError(#[from] ExternalError),
+    #[error("Invalid window icon: {0}")]
+    BadIcon(#[from] BadIcon),
+    #[error("Invalid cursor image: {0}")]
+    InvalidCursorImage(String),
+    #[error("Custom cursors are not supported on this platform")]
+    CustomCursorNotSupported,
+    #[error("Invalid aspect ratio {0}")]
+    InvalidAspectRatio(f64),
+    #[error("X11 library {0} is not available")]
+    X11LibraryNotAvailable(&'static str),
+    #[error("The window is not an X11 window")]
+    NotX11Window,
+    #[error("Invalid window configuration: {0}")]
+    InvalidWindowConfig(WindowConfigError),
+    #[error("Failed to serialize the window configuration: {0}")]
+    WindowConfigSerialization(String),
     #[error("Boxer error")]
     BoxerError(#[from] BoxerError),
 }
@@ -292,6 +292,7 @@ impl Default for WinitEventInputElementState {
 ////////////////////////////////////// E V E N T S ////////////////////////////////////
 ///////////////////////////////////////////////////////////////////////////////////////
 
+#[derive(Debug)]
 pub struct EventProcessor {
     pub key_buffer: HashMap<ScanCode, VirtualKeyCode>,
 }
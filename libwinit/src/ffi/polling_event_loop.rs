@@ -1,16 +1,26 @@
 use std::ffi::c_void;
 use std::mem::transmute;
+#[cfg(run_return_platform)]
+use std::time::Duration;
 
-use value_box::{ReturnBoxerResult, ValueBox, ValueBoxIntoRaw, ValueBoxPointer};
-use winit::window::{WindowBuilder, WindowId};
+use geometry_box::U128Box;
+use value_box::{Result, ReturnBoxerResult, ValueBox, ValueBoxIntoRaw, ValueBoxPointer};
+use winit::monitor::MonitorHandle;
+use winit::window::WindowId;
 
 use crate::event_loop::WinitEventLoopType;
 use crate::events::WinitEvent;
 use crate::{
-    PollingEventLoop, WindowRedrawRequestedListener, WindowRef, WindowResizedListener,
-    WinitEventLoopWaker, WinitUserEvent,
+    DispatchedCallback, PollingEventLoop, WindowRedrawRequestedListener, WindowRef,
+    WindowResizedListener, WinitEventLoopWaker, WinitUserEvent, WinitWindowBuilder,
 };
 
+fn find_window_id(event_loop: &PollingEventLoop, window_id: &U128Box) -> Result<WindowId> {
+    event_loop
+        .find_window_id(window_id)
+        .ok_or_else(|| format!("Window with id {:?} not found", window_id).into())
+}
+
 #[no_mangle]
 pub extern "C" fn winit_waker_wake(waker: *const c_void, event: WinitUserEvent) -> bool {
     let waker = waker as *mut ValueBox<WinitEventLoopWaker>;
@@ -60,10 +70,84 @@ pub extern "C" fn winit_polling_event_loop_wake(
         .or_log(false)
 }
 
+/// Request the event loop to exit with the given exit code. Can be called from any thread.
+/// All windows are destroyed and `LoopDestroyed` is delivered to the host.
+/// On X11 and Wayland [`winit_polling_event_loop_run`] then returns to the caller.
+#[no_mangle]
+pub extern "C" fn winit_polling_event_loop_request_exit(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    exit_code: i32,
+) -> bool {
+    event_loop
+        .with_ref(|event_loop| {
+            event_loop
+                .request_exit(exit_code)
+                .map_err(|error| error.boxed().into())
+        })
+        .map(|_| true)
+        .or_log(false)
+}
+
+/// Queue a callback to be run on the event loop thread with the event loop running.
+/// An optional completion callback is called on the event loop thread after it.
+/// Returns `false` once the event loop has exited. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_polling_event_loop_dispatch(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    callback: unsafe extern "C" fn(*const c_void),
+    thunk: *const c_void,
+    completion_callback: Option<unsafe extern "C" fn(*const c_void)>,
+    completion_thunk: *const c_void,
+) -> bool {
+    event_loop
+        .with_ref(|event_loop| {
+            let mut dispatched_callback = DispatchedCallback::new(callback, thunk);
+            if let Some(completion_callback) = completion_callback {
+                dispatched_callback = dispatched_callback
+                    .with_completion_callback(completion_callback, completion_thunk);
+            }
+            event_loop
+                .dispatch(dispatched_callback)
+                .map_err(|error| error.boxed().into())
+        })
+        .map(|_| true)
+        .or_log(false)
+}
+
+/// Run a callback on the event loop thread and wait for it to finish.
+/// Returns `false` if the event loop was never started or exited before the callback could run.
+/// Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_polling_event_loop_dispatch_blocking(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    callback: unsafe extern "C" fn(*const c_void),
+    thunk: *const c_void,
+) -> bool {
+    event_loop
+        .with_ref(|event_loop| {
+            event_loop
+                .dispatch_blocking(DispatchedCallback::new(callback, thunk))
+                .map_err(|error| error.boxed().into())
+        })
+        .map(|_| true)
+        .or_log(false)
+}
+
+/// Call a given callback on the event loop thread once all window commands
+/// that were sent before have been applied. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_polling_event_loop_notify_window_commands_applied(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    callback: unsafe extern "C" fn(*const c_void),
+    thunk: *const c_void,
+) -> bool {
+    winit_polling_event_loop_dispatch(event_loop, callback, thunk, None, std::ptr::null())
+}
+
 #[no_mangle]
 pub extern "C" fn winit_polling_event_loop_create_window(
     event_loop: *mut ValueBox<PollingEventLoop>,
-    window_builder: *mut ValueBox<WindowBuilder>,
+    window_builder: *mut ValueBox<WinitWindowBuilder>,
 ) -> *mut ValueBox<WindowRef> {
     event_loop
         .with_mut(|event_loop| {
@@ -77,6 +161,115 @@ pub extern "C" fn winit_polling_event_loop_create_window(
         .or_log(std::ptr::null_mut())
 }
 
+/// Return the amount of live windows. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_polling_event_loop_window_count(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+) -> usize {
+    event_loop
+        .with_ref_ok(PollingEventLoop::window_count)
+        .or_log(0)
+}
+
+/// Return a new reference to a live window at a given index (starting from 0)
+/// or null if the index is out of bounds. Windows can be enumerated together with
+/// [`winit_polling_event_loop_window_count`]. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_polling_event_loop_get_window_at(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    index: usize,
+) -> *mut ValueBox<WindowRef> {
+    event_loop
+        .with_ref_ok(|event_loop| {
+            event_loop
+                .window_refs()
+                .into_iter()
+                .nth(index)
+                .map(|window_ref| ValueBox::new(window_ref).into_raw())
+                .unwrap_or(std::ptr::null_mut())
+        })
+        .or_log(std::ptr::null_mut())
+}
+
+/// Return a new reference to a window with the id that is sent as part of events
+/// or null if there is no such window. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_polling_event_loop_find_window(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_id: *mut ValueBox<U128Box>,
+) -> *mut ValueBox<WindowRef> {
+    event_loop
+        .with_ref(|event_loop| {
+            window_id.with_ref_ok(|window_id| {
+                event_loop
+                    .find_window(window_id)
+                    .map(|window_ref| ValueBox::new(window_ref).into_raw())
+                    .unwrap_or(std::ptr::null_mut())
+            })
+        })
+        .or_log(std::ptr::null_mut())
+}
+
+/// Return the amount of available monitors.
+/// Must be called from the event loop thread while the event loop is running.
+#[no_mangle]
+pub extern "C" fn winit_polling_event_loop_monitor_count(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+) -> usize {
+    event_loop
+        .with_ref(|event_loop| {
+            event_loop
+                .available_monitors()
+                .map(|monitors| monitors.len())
+                .map_err(|error| error.boxed().into())
+        })
+        .or_log(0)
+}
+
+/// Return an available monitor at a given index (starting from 0)
+/// or null if the index is out of bounds.
+/// Must be called from the event loop thread while the event loop is running.
+#[no_mangle]
+pub extern "C" fn winit_polling_event_loop_get_monitor_at(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    index: usize,
+) -> *mut ValueBox<MonitorHandle> {
+    event_loop
+        .with_ref(|event_loop| {
+            event_loop
+                .available_monitors()
+                .map(|monitors| {
+                    monitors
+                        .into_iter()
+                        .nth(index)
+                        .map(|monitor| ValueBox::new(monitor).into_raw())
+                        .unwrap_or(std::ptr::null_mut())
+                })
+                .map_err(|error| error.boxed().into())
+        })
+        .or_log(std::ptr::null_mut())
+}
+
+/// Return the primary monitor or null if it can not be detected.
+/// Must be called from the event loop thread while the event loop is running.
+#[no_mangle]
+pub extern "C" fn winit_polling_event_loop_get_primary_monitor(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+) -> *mut ValueBox<MonitorHandle> {
+    event_loop
+        .with_ref(|event_loop| {
+            event_loop
+                .primary_monitor()
+                .map(|monitor| {
+                    monitor
+                        .map(|monitor| ValueBox::new(monitor).into_raw())
+                        .unwrap_or(std::ptr::null_mut())
+                })
+                .map_err(|error| error.boxed().into())
+        })
+        .or_log(std::ptr::null_mut())
+}
+
 #[no_mangle]
 pub extern "C" fn winit_polling_event_loop_new_with_semaphore_and_main_events_signaller(
     semaphore_callback: extern "C" fn(usize, *const c_void),
@@ -93,18 +286,23 @@ pub extern "C" fn winit_polling_event_loop_new_with_semaphore_and_main_events_si
     .into_raw()
 }
 
+/// Add a resize listener to a window with the id that is sent as part of events
 #[no_mangle]
 pub extern "C" fn winit_polling_event_loop_add_resize_listener(
     event_loop: *mut ValueBox<PollingEventLoop>,
-    window_id: *mut ValueBox<WindowId>,
+    window_id: *mut ValueBox<U128Box>,
     callback: unsafe extern "C" fn(*const c_void, u32, u32),
     thunk: *const c_void,
 ) {
     event_loop
         .with_mut(|event_loop| {
             window_id.with_ref(|window_id| {
-                Ok(event_loop
-                    .add_resize_listener(window_id, WindowResizedListener::new(callback, thunk)))
+                find_window_id(event_loop, window_id).map(|window_id| {
+                    event_loop.add_resize_listener(
+                        &window_id,
+                        WindowResizedListener::new(callback, thunk),
+                    )
+                })
             })
         })
         .log();
@@ -132,20 +330,23 @@ pub extern "C" fn winit_polling_event_loop_count_resize_listeners(
         .or_log(0)
 }
 
+/// Add a redraw listener to a window with the id that is sent as part of events
 #[no_mangle]
 pub extern "C" fn winit_polling_event_loop_add_redraw_listener(
     event_loop: *mut ValueBox<PollingEventLoop>,
-    window_id: *mut ValueBox<WindowId>,
+    window_id: *mut ValueBox<U128Box>,
     callback: unsafe extern "C" fn(*const c_void),
     thunk: *const c_void,
 ) {
     event_loop
         .with_mut(|event_loop| {
             window_id.with_ref(|window_id| {
-                Ok(event_loop.add_redraw_listener(
-                    window_id,
-                    WindowRedrawRequestedListener::new(callback, thunk),
-                ))
+                find_window_id(event_loop, window_id).map(|window_id| {
+                    event_loop.add_redraw_listener(
+                        &window_id,
+                        WindowRedrawRequestedListener::new(callback, thunk),
+                    )
+                })
             })
         })
         .log();
@@ -206,6 +407,20 @@ pub extern "C" fn winit_polling_event_loop_run(event_loop: *mut ValueBox<Polling
         .log();
 }
 
+/// Process pending events and return, waiting at most `timeout_ms` milliseconds for new events.
+/// Can be used instead of [`winit_polling_event_loop_run`] to drive the event loop step by step.
+/// Returns `false` once the event loop has exited. Must be called from the main thread.
+#[cfg(run_return_platform)]
+#[no_mangle]
+pub extern "C" fn winit_polling_event_loop_pump(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    timeout_ms: u64,
+) -> bool {
+    event_loop
+        .with_mut_ok(|event_loop| event_loop.pump(Duration::from_millis(timeout_ms)))
+        .or_log(false)
+}
+
 /// Must be called from the inside of the `run` method of the [`PollingEventLoop`].
 #[no_mangle]
 pub extern "C" fn winit_polling_event_loop_get_type(
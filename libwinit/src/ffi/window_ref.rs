@@ -1,5 +1,5 @@
 use raw_window_handle::{HasRawDisplayHandle, HasRawWindowHandle};
-use winit::dpi::{PhysicalPosition, PhysicalSize};
+use winit::dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize};
 #[cfg(target_os = "ios")]
 use winit::platform::ios::WindowExtIOS;
 #[cfg(target_os = "macos")]
@@ -10,15 +10,20 @@ use winit::platform::wayland::WindowExtWayland;
 use winit::platform::windows::WindowExtWindows;
 #[cfg(x11_platform)]
 use winit::platform::x11::WindowExtX11;
-use winit::window::{Window, WindowId};
+use winit::window::{Window, WindowButtons, WindowId};
 
-use crate::enums::WinitCursorIcon;
-use crate::{winit_convert_window_id, PollingEventLoop, WindowRef};
+use crate::enums::{
+    WinitCursorGrabMode, WinitCursorIcon, WinitFullscreen, WinitResizeDirection, WinitTheme,
+    WinitUserAttention, WinitWindowLevel,
+};
+use crate::monitor::{borderless_fullscreen, exclusive_fullscreen};
+use crate::window_builder::icon_from_rgba;
+use crate::{CustomCursor, FrameInsets, PollingEventLoop, WindowCommand, WindowRef, WinitError};
 use geometry_box::{PointBox, SizeBox, U128Box};
 use raw_window_handle_extensions::{VeryRawDisplayHandle, VeryRawWindowHandle};
 use string_box::StringBox;
 use value_box::{Result, ReturnBoxerResult, ValueBox, ValueBoxIntoRaw, ValueBoxPointer};
-use winit::monitor::MonitorHandle;
+use winit::monitor::{MonitorHandle, VideoMode};
 
 fn with_window<T: 'static>(
     event_loop: *mut ValueBox<PollingEventLoop>,
@@ -36,17 +41,32 @@ fn with_window<T: 'static>(
     })
 }
 
-fn with_window_mut<T: 'static>(
+/// Apply a command to the window on the event loop thread.
+/// When called from any other thread the command is queued.
+fn send_window_command(
     event_loop: *mut ValueBox<PollingEventLoop>,
     window_ref: *mut ValueBox<WindowRef>,
-    callback: impl FnOnce(&mut Window, &mut WindowRef) -> Result<T>,
-) -> Result<T> {
-    event_loop.with_mut(|event_loop| {
-        window_ref.with_mut(|window_ref| {
+    command: WindowCommand,
+) -> Result<()> {
+    event_loop.with_ref(|event_loop| {
+        window_ref.with_ref(|window_ref| {
             event_loop
-                .with_window_mut(&window_ref.id(), |window, _| {
-                    callback(window, window_ref).map_err(|error| error.into())
-                })
+                .send_window_command(window_ref, command)
+                .map_err(|err| err.boxed().into())
+        })
+    })
+}
+
+/// Apply a command to the window on the event loop thread and wait for the result.
+fn send_window_command_blocking(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+    command: WindowCommand,
+) -> Result<()> {
+    event_loop.with_ref(|event_loop| {
+        window_ref.with_ref(|window_ref| {
+            event_loop
+                .send_window_command_blocking(window_ref, command)
                 .map_err(|err| err.boxed().into())
         })
     })
@@ -86,10 +106,7 @@ pub extern "C" fn winit_window_ref_request_redraw(
     event_loop: *mut ValueBox<PollingEventLoop>,
     window_ref: *mut ValueBox<WindowRef>,
 ) {
-    with_window(event_loop, window_ref, |window, _event_loop| {
-        Ok(window.request_redraw())
-    })
-    .log();
+    send_window_command(event_loop, window_ref, WindowCommand::RequestRedraw).log();
 }
 
 /// Get the scaled factor of the window. Can be called from the any thread.
@@ -122,8 +139,7 @@ pub extern "C" fn winit_window_ref_get_inner_size(
         .log();
 }
 
-/// Set the inner size of the window.
-/// Must be called from the main thread
+/// Set the inner size of the window. Can be called from any thread.
 #[no_mangle]
 pub extern "C" fn winit_window_ref_set_inner_size(
     event_loop: *mut ValueBox<PollingEventLoop>,
@@ -131,16 +147,96 @@ pub extern "C" fn winit_window_ref_set_inner_size(
     width: u32,
     height: u32,
 ) {
-    with_window_mut(event_loop, window_ref, |window, window_ref| {
-        let new_size = PhysicalSize::new(width, height);
-        window_ref
-            .set_inner_size(new_size)
-            .map_err(|err| err.boxed().into())
-            .map(|_| window.set_inner_size(new_size.clone()))
-    })
+    send_window_command(
+        event_loop,
+        window_ref,
+        WindowCommand::SetInnerSize(PhysicalSize::new(width, height)),
+    )
     .log();
 }
 
+/// Set the minimum inner size of the window in physical pixels. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_set_min_inner_size(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+    width: u32,
+    height: u32,
+) {
+    send_window_command(
+        event_loop,
+        window_ref,
+        WindowCommand::SetMinInnerSize(Some(PhysicalSize::new(width, height).into())),
+    )
+    .log();
+}
+
+/// Set the minimum inner size of the window in logical pixels. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_set_min_logical_inner_size(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+    width: f64,
+    height: f64,
+) {
+    send_window_command(
+        event_loop,
+        window_ref,
+        WindowCommand::SetMinInnerSize(Some(LogicalSize::new(width, height).into())),
+    )
+    .log();
+}
+
+/// Remove the minimum inner size constraint. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_clear_min_inner_size(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+) {
+    send_window_command(event_loop, window_ref, WindowCommand::SetMinInnerSize(None)).log();
+}
+
+/// Set the maximum inner size of the window in physical pixels. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_set_max_inner_size(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+    width: u32,
+    height: u32,
+) {
+    send_window_command(
+        event_loop,
+        window_ref,
+        WindowCommand::SetMaxInnerSize(Some(PhysicalSize::new(width, height).into())),
+    )
+    .log();
+}
+
+/// Set the maximum inner size of the window in logical pixels. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_set_max_logical_inner_size(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+    width: f64,
+    height: f64,
+) {
+    send_window_command(
+        event_loop,
+        window_ref,
+        WindowCommand::SetMaxInnerSize(Some(LogicalSize::new(width, height).into())),
+    )
+    .log();
+}
+
+/// Remove the maximum inner size constraint. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_clear_max_inner_size(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+) {
+    send_window_command(event_loop, window_ref, WindowCommand::SetMaxInnerSize(None)).log();
+}
+
 /// Get the outer position of the window. Can be called from the any thread.
 #[no_mangle]
 pub extern "C" fn winit_window_ref_get_position(
@@ -163,8 +259,242 @@ pub extern "C" fn winit_window_ref_get_position(
         .log();
 }
 
-/// Set the outer position of the window.
-/// Must be called from the main thread
+/// Return the size of the window including decorations. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_get_outer_size(
+    window_ref: *mut ValueBox<WindowRef>,
+    outer_size: *mut ValueBox<SizeBox<u32>>,
+) {
+    window_ref
+        .with_ref(|window_ref| {
+            outer_size.with_mut(|outer_size| {
+                window_ref
+                    .outer_size()
+                    .map_err(|err| err.boxed().into())
+                    .map(|window_size| {
+                        outer_size.width = window_size.width;
+                        outer_size.height = window_size.height;
+                    })
+            })
+        })
+        .log();
+}
+
+/// Return the position of the client area of the window. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_get_inner_position(
+    window_ref: *mut ValueBox<WindowRef>,
+    position: *mut ValueBox<PointBox<i32>>,
+) {
+    window_ref
+        .with_ref(|window_ref| {
+            position.with_mut(|position| {
+                window_ref
+                    .inner_position()
+                    .map_err(|err| err.boxed().into())
+                    .map(|window_position| {
+                        position.x = window_position.x;
+                        position.y = window_position.y;
+                    })
+            })
+        })
+        .log();
+}
+
+/// Return the thickness of the window decorations in physical pixels.
+/// Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_get_frame_insets(
+    window_ref: *mut ValueBox<WindowRef>,
+) -> FrameInsets<i32> {
+    window_ref
+        .with_ref(|window_ref| window_ref.frame_insets().map_err(|err| err.boxed().into()))
+        .or_log(Default::default())
+}
+
+/// Return the thickness of the window decorations in logical pixels.
+/// Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_get_logical_frame_insets(
+    window_ref: *mut ValueBox<WindowRef>,
+) -> FrameInsets<f64> {
+    window_ref
+        .with_ref(|window_ref| {
+            window_ref
+                .frame_insets()
+                .and_then(|insets| Ok(insets.to_logical(window_ref.scale_factor()?)))
+                .map_err(|err| err.boxed().into())
+        })
+        .or_log(Default::default())
+}
+
+/// Return true if the window has keyboard focus. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_is_focused(window_ref: *mut ValueBox<WindowRef>) -> bool {
+    window_ref
+        .with_ref(|window_ref| window_ref.is_focused().map_err(|err| err.boxed().into()))
+        .or_log(false)
+}
+
+/// Return true if the cursor is inside of the window. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_is_hovered(window_ref: *mut ValueBox<WindowRef>) -> bool {
+    window_ref
+        .with_ref(|window_ref| window_ref.is_hovered().map_err(|err| err.boxed().into()))
+        .or_log(false)
+}
+
+/// Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_is_minimized(window_ref: *mut ValueBox<WindowRef>) -> bool {
+    window_ref
+        .with_ref(|window_ref| window_ref.is_minimized().map_err(|err| err.boxed().into()))
+        .or_log(false)
+}
+
+/// Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_is_maximized(window_ref: *mut ValueBox<WindowRef>) -> bool {
+    window_ref
+        .with_ref(|window_ref| window_ref.is_maximized().map_err(|err| err.boxed().into()))
+        .or_log(false)
+}
+
+/// Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_is_visible(window_ref: *mut ValueBox<WindowRef>) -> bool {
+    window_ref
+        .with_ref(|window_ref| window_ref.is_visible().map_err(|err| err.boxed().into()))
+        .or_log(false)
+}
+
+/// Return true if the window is fully hidden by other windows or is not on the current
+/// workspace. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_is_occluded(window_ref: *mut ValueBox<WindowRef>) -> bool {
+    window_ref
+        .with_ref(|window_ref| window_ref.is_occluded().map_err(|err| err.boxed().into()))
+        .or_log(false)
+}
+
+/// Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_is_decorated(window_ref: *mut ValueBox<WindowRef>) -> bool {
+    window_ref
+        .with_ref(|window_ref| window_ref.is_decorated().map_err(|err| err.boxed().into()))
+        .or_log(true)
+}
+
+/// Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_is_resizable(window_ref: *mut ValueBox<WindowRef>) -> bool {
+    window_ref
+        .with_ref(|window_ref| window_ref.is_resizable().map_err(|err| err.boxed().into()))
+        .or_log(true)
+}
+
+/// Return the transparency that was last requested for the window. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_is_transparent(window_ref: *mut ValueBox<WindowRef>) -> bool {
+    window_ref
+        .with_ref(|window_ref| {
+            window_ref
+                .is_transparent()
+                .map_err(|err| err.boxed().into())
+        })
+        .or_log(false)
+}
+
+/// Return the window level that was last requested for the window. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_get_window_level(
+    window_ref: *mut ValueBox<WindowRef>,
+) -> WinitWindowLevel {
+    window_ref
+        .with_ref(|window_ref| {
+            window_ref
+                .window_level()
+                .map(|level| level.into())
+                .map_err(|err| err.boxed().into())
+        })
+        .or_log(WinitWindowLevel::Normal)
+}
+
+/// Return the enabled titlebar buttons as bit flags:
+/// close = 1, minimize = 2, maximize = 4. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_get_enabled_buttons(
+    window_ref: *mut ValueBox<WindowRef>,
+) -> u32 {
+    window_ref
+        .with_ref(|window_ref| {
+            window_ref
+                .enabled_buttons()
+                .map(|buttons| buttons.bits())
+                .map_err(|err| err.boxed().into())
+        })
+        .or_log(WindowButtons::all().bits())
+}
+
+/// Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_get_theme(window_ref: *mut ValueBox<WindowRef>) -> WinitTheme {
+    window_ref
+        .with_ref(|window_ref| {
+            window_ref
+                .theme()
+                .map(|theme| theme.into())
+                .map_err(|err| err.boxed().into())
+        })
+        .or_log(WinitTheme::Unknown)
+}
+
+/// Get the title of the window. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_get_title(
+    window_ref: *mut ValueBox<WindowRef>,
+    title: *mut ValueBox<StringBox>,
+) {
+    window_ref
+        .with_ref(|window_ref| {
+            title.with_mut(|title| {
+                window_ref
+                    .title()
+                    .map_err(|err| err.boxed().into())
+                    .map(|window_title| title.set_string(window_title))
+            })
+        })
+        .log();
+}
+
+/// Get the last known cursor position relative to the window.
+/// Returns false if the cursor has not been inside of the window yet.
+/// Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_get_cursor_position(
+    window_ref: *mut ValueBox<WindowRef>,
+    position: *mut ValueBox<PointBox<f64>>,
+) -> bool {
+    window_ref
+        .with_ref(|window_ref| {
+            position.with_mut(|position| {
+                window_ref
+                    .cursor_position()
+                    .map_err(|err| err.boxed().into())
+                    .map(|cursor_position| match cursor_position {
+                        None => false,
+                        Some(cursor_position) => {
+                            position.x = cursor_position.x;
+                            position.y = cursor_position.y;
+                            true
+                        }
+                    })
+            })
+        })
+        .or_log(false)
+}
+
+/// Set the outer position of the window. Can be called from any thread.
 #[no_mangle]
 pub extern "C" fn winit_window_ref_set_position(
     event_loop: *mut ValueBox<PollingEventLoop>,
@@ -172,16 +502,16 @@ pub extern "C" fn winit_window_ref_set_position(
     x: i32,
     y: i32,
 ) {
-    with_window_mut(event_loop, window_ref, |window, window_ref| {
-        let new_position = PhysicalPosition::new(x, y);
-        window_ref
-            .set_outer_position(new_position.clone())
-            .map_err(|err| err.boxed().into())
-            .map(|_| window.set_outer_position(new_position))
-    })
+    send_window_command(
+        event_loop,
+        window_ref,
+        WindowCommand::SetOuterPosition(PhysicalPosition::new(x, y)),
+    )
     .log();
 }
 
+/// Get the id of the window that is sent as part of events and is accepted by
+/// `winit_polling_event_loop_find_window`. Can be called from any thread.
 #[no_mangle]
 pub extern "C" fn winit_window_ref_get_id(
     window_ref: *mut ValueBox<WindowRef>,
@@ -190,7 +520,7 @@ pub extern "C" fn winit_window_ref_get_id(
     window_ref
         .with_ref(|window_ref| {
             id.with_mut(|id| {
-                let window_id: U128Box = winit_convert_window_id(window_ref.id().clone());
+                let window_id: U128Box = (window_ref.host_id() as u128).into();
                 id.low = window_id.low;
                 id.high = window_id.high;
                 Ok(())
@@ -199,6 +529,7 @@ pub extern "C" fn winit_window_ref_get_id(
         .log();
 }
 
+/// Get the window id as assigned by winit
 #[no_mangle]
 pub extern "C" fn winit_window_ref_get_raw_id(
     window_ref: *mut ValueBox<WindowRef>,
@@ -208,58 +539,666 @@ pub extern "C" fn winit_window_ref_get_raw_id(
         .into_raw()
 }
 
+/// Can be called from any thread.
 #[no_mangle]
 pub extern "C" fn winit_window_ref_set_title(
     event_loop: *mut ValueBox<PollingEventLoop>,
     window_ref: *mut ValueBox<WindowRef>,
     title: *mut ValueBox<StringBox>,
 ) {
-    with_window_mut(event_loop, window_ref, |window, _window_ref| {
-        title.with_ref(|title| {
-            window.set_title(title.to_string().as_ref());
-            Ok(())
+    title
+        .with_ref_ok(|title| title.to_string())
+        .and_then(|title| {
+            send_window_command(event_loop, window_ref, WindowCommand::SetTitle(title))
         })
-    })
-    .log();
+        .log();
 }
 
+/// Can be called from any thread.
 #[no_mangle]
 pub extern "C" fn winit_window_ref_set_cursor_icon(
     event_loop: *mut ValueBox<PollingEventLoop>,
     window_ref: *mut ValueBox<WindowRef>,
     cursor_icon: WinitCursorIcon,
 ) {
-    with_window_mut(event_loop, window_ref, |window, _window_ref| {
-        window.set_cursor_icon(cursor_icon.into());
-        Ok(())
-    })
+    send_window_command(
+        event_loop,
+        window_ref,
+        WindowCommand::SetCursorIcon(cursor_icon.into()),
+    )
+    .log();
+}
+
+/// Start moving the window with the mouse, for windows that draw their own title bar.
+/// Must be called from a mouse press handler while the button is still pressed.
+/// Return false if the platform refused.
+/// Can be called from any thread, in which case it waits for the event loop.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_drag_window(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+) -> bool {
+    send_window_command_blocking(event_loop, window_ref, WindowCommand::DragWindow)
+        .map(|_| true)
+        .or_log(false)
+}
+
+/// Start resizing the window with the mouse in a given direction, for windows without
+/// decorations. Must be called from a mouse press handler while the button is still pressed.
+/// Return false if the platform refused.
+/// Can be called from any thread, in which case it waits for the event loop.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_drag_resize_window(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+    direction: WinitResizeDirection,
+) -> bool {
+    send_window_command_blocking(
+        event_loop,
+        window_ref,
+        WindowCommand::DragResizeWindow(direction.into()),
+    )
+    .map(|_| true)
+    .or_log(false)
+}
+
+/// When `hittest` is false the window ignores mouse input, which passes through to the
+/// windows below. Return false if the platform refused.
+/// Can be called from any thread, in which case it waits for the event loop.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_set_cursor_hittest(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+    hittest: bool,
+) -> bool {
+    send_window_command_blocking(
+        event_loop,
+        window_ref,
+        WindowCommand::SetCursorHittest(hittest),
+    )
+    .map(|_| true)
+    .or_log(false)
+}
+
+/// Only let the given rectangles of the window receive mouse input, clicks on the rest of
+/// the window pass through to the windows below. Rectangles are given as `count` quadruples
+/// of `x, y, width, height` in physical pixels. Return false if the window is not an X11 window.
+/// Can be called from any thread, in which case it waits for the event loop.
+/// # Safety
+/// `rectangles` must point to at least `count * 4` integers
+#[no_mangle]
+pub unsafe extern "C" fn winit_window_ref_set_x11_input_shape(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+    rectangles: *const i32,
+    count: usize,
+) -> bool {
+    let shape = if rectangles.is_null() {
+        vec![]
+    } else {
+        std::slice::from_raw_parts(rectangles, count * 4)
+            .chunks_exact(4)
+            .map(|rectangle| {
+                (
+                    PhysicalPosition::new(rectangle[0], rectangle[1]),
+                    PhysicalSize::new(rectangle[2].max(0) as u32, rectangle[3].max(0) as u32),
+                )
+            })
+            .collect()
+    };
+
+    send_window_command_blocking(
+        event_loop,
+        window_ref,
+        WindowCommand::SetInputShape(Some(shape)),
+    )
+    .map(|_| true)
+    .or_log(false)
+}
+
+/// Let the whole window receive mouse input again. Return false if the window is not
+/// an X11 window. Can be called from any thread, in which case it waits for the event loop.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_clear_x11_input_shape(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+) -> bool {
+    send_window_command_blocking(event_loop, window_ref, WindowCommand::SetInputShape(None))
+        .map(|_| true)
+        .or_log(false)
+}
+
+/// Request the user's attention, for example by flashing the taskbar entry.
+/// On X11 this sets the urgency hint. Has no effect if the window is focused.
+/// `None` cancels a previous request. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_request_user_attention(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+    attention: WinitUserAttention,
+) {
+    send_window_command(
+        event_loop,
+        window_ref,
+        WindowCommand::RequestUserAttention(attention.into()),
+    )
     .log();
 }
 
+/// Allow or disallow input from input methods. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_set_ime_allowed(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+    allowed: bool,
+) {
+    send_window_command(
+        event_loop,
+        window_ref,
+        WindowCommand::SetImeAllowed(allowed),
+    )
+    .log();
+}
+
+/// Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_is_ime_allowed(window_ref: *mut ValueBox<WindowRef>) -> bool {
+    window_ref
+        .with_ref(|window_ref| {
+            window_ref
+                .is_ime_allowed()
+                .map_err(|err| err.boxed().into())
+        })
+        .or_log(false)
+}
+
+/// Tell the input method where the text caret is, in physical pixels relative to the window.
+/// winit 0.28 only accepts a position, so the candidate window is placed below the area
+/// and the width is not used yet. The whole area is kept with the window reference.
+/// Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_set_ime_cursor_area(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+) {
+    send_window_command(
+        event_loop,
+        window_ref,
+        WindowCommand::SetImeCursorArea(
+            PhysicalPosition::new(x, y).into(),
+            PhysicalSize::new(width, height).into(),
+        ),
+    )
+    .log();
+}
+
+/// Tell the input method where the text caret is, in logical pixels relative to the window.
+/// winit 0.28 only accepts a position, so the candidate window is placed below the area
+/// and the width is not used yet. The whole area is kept with the window reference.
+/// Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_set_ime_logical_cursor_area(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+) {
+    send_window_command(
+        event_loop,
+        window_ref,
+        WindowCommand::SetImeCursorArea(
+            LogicalPosition::new(x, y).into(),
+            LogicalSize::new(width, height).into(),
+        ),
+    )
+    .log();
+}
+
+/// Set the window icon from RGBA8 pixels. Return false if the icon is not valid.
+/// Can be called from any thread.
+/// # Safety
+/// `rgba` must point to at least `length` bytes
+#[no_mangle]
+pub unsafe extern "C" fn winit_window_ref_set_window_icon_rgba(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+    width: u32,
+    height: u32,
+    rgba: *const u8,
+    length: usize,
+) -> bool {
+    icon_from_rgba(width, height, rgba, length)
+        .map_err(|err| err.boxed().into())
+        .and_then(|icon| {
+            send_window_command(
+                event_loop,
+                window_ref,
+                WindowCommand::SetWindowIcon(Some(icon)),
+            )
+        })
+        .map(|_| true)
+        .or_log(false)
+}
+
+/// Remove the window icon. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_clear_window_icon(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+) {
+    send_window_command(event_loop, window_ref, WindowCommand::SetWindowIcon(None)).log();
+}
+
+/// Show a custom cursor over the window, picking the image variant for the window's
+/// scale factor. Setting a cursor icon replaces it. Return false if custom cursors are not
+/// supported (only X11 is supported for now).
+/// Can be called from any thread, in which case it waits for the event loop.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_set_cursor(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+    cursor: *mut ValueBox<CustomCursor>,
+) -> bool {
+    cursor
+        .with_ref_ok(|cursor| cursor.clone())
+        .and_then(|cursor| {
+            send_window_command_blocking(
+                event_loop,
+                window_ref,
+                WindowCommand::SetCustomCursor(cursor),
+            )
+        })
+        .map(|_| true)
+        .or_log(false)
+}
+
+/// Grab the cursor. The grab is released while the window is not focused and is restored
+/// when the window gains focus. Return false if the platform does not support the mode,
+/// in which case the previous mode is kept. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_set_cursor_grab(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+    mode: WinitCursorGrabMode,
+) -> bool {
+    send_window_command_blocking(
+        event_loop,
+        window_ref,
+        WindowCommand::SetCursorGrab(mode.into()),
+    )
+    .map(|_| true)
+    .or_log(false)
+}
+
+/// Return the requested cursor grab mode. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_get_cursor_grab(
+    window_ref: *mut ValueBox<WindowRef>,
+) -> WinitCursorGrabMode {
+    window_ref
+        .with_ref(|window_ref| {
+            window_ref
+                .cursor_grab()
+                .map(|mode| mode.into())
+                .map_err(|err| err.boxed().into())
+        })
+        .or_log(WinitCursorGrabMode::None)
+}
+
+/// Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_set_cursor_visible(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+    visible: bool,
+) {
+    send_window_command(
+        event_loop,
+        window_ref,
+        WindowCommand::SetCursorVisible(visible),
+    )
+    .log();
+}
+
+/// Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_is_cursor_visible(window_ref: *mut ValueBox<WindowRef>) -> bool {
+    window_ref
+        .with_ref(|window_ref| {
+            window_ref
+                .is_cursor_visible()
+                .map_err(|err| err.boxed().into())
+        })
+        .or_log(true)
+}
+
+/// Move the cursor to a physical position relative to the top-left corner of the window.
+/// Return false if the platform refused to move the cursor (e.g. Wayland).
+/// Can be called from any thread, in which case it waits for the event loop.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_set_cursor_position(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+    x: f64,
+    y: f64,
+) -> bool {
+    send_window_command_blocking(
+        event_loop,
+        window_ref,
+        WindowCommand::SetCursorPosition(PhysicalPosition::new(x, y).into()),
+    )
+    .map(|_| true)
+    .or_log(false)
+}
+
+/// Move the cursor to a logical position relative to the top-left corner of the window.
+/// Return false if the platform refused to move the cursor (e.g. Wayland).
+/// Can be called from any thread, in which case it waits for the event loop.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_set_cursor_logical_position(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+    x: f64,
+    y: f64,
+) -> bool {
+    send_window_command_blocking(
+        event_loop,
+        window_ref,
+        WindowCommand::SetCursorPosition(LogicalPosition::new(x, y).into()),
+    )
+    .map(|_| true)
+    .or_log(false)
+}
+
+/// Can be called from any thread.
 #[no_mangle]
 pub extern "C" fn winit_window_ref_set_maximized(
     event_loop: *mut ValueBox<PollingEventLoop>,
     window_ref: *mut ValueBox<WindowRef>,
     maximized: bool,
 ) {
-    with_window_mut(event_loop, window_ref, |window, _window_ref| {
-        window.set_maximized(maximized);
-        Ok(())
-    })
+    send_window_command(
+        event_loop,
+        window_ref,
+        WindowCommand::SetMaximized(maximized),
+    )
     .log();
 }
 
+/// Can be called from any thread.
 #[no_mangle]
-pub extern "C" fn winit_window_ref_focus_window(
+pub extern "C" fn winit_window_ref_set_decorations(
     event_loop: *mut ValueBox<PollingEventLoop>,
     window_ref: *mut ValueBox<WindowRef>,
+    decorations: bool,
 ) {
-    with_window(event_loop, window_ref, |window, _event_loop| {
-        Ok(window.focus_window())
-    })
+    send_window_command(
+        event_loop,
+        window_ref,
+        WindowCommand::SetDecorations(decorations),
+    )
     .log();
 }
 
+/// Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_set_resizable(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+    resizable: bool,
+) {
+    send_window_command(
+        event_loop,
+        window_ref,
+        WindowCommand::SetResizable(resizable),
+    )
+    .log();
+}
+
+/// Only takes effect on macOS and Wayland. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_set_transparent(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+    transparent: bool,
+) {
+    send_window_command(
+        event_loop,
+        window_ref,
+        WindowCommand::SetTransparent(transparent),
+    )
+    .log();
+}
+
+/// Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_set_window_level(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+    level: WinitWindowLevel,
+) {
+    send_window_command(
+        event_loop,
+        window_ref,
+        WindowCommand::SetWindowLevel(level.into()),
+    )
+    .log();
+}
+
+/// Enable titlebar buttons given as bit flags: close = 1, minimize = 2, maximize = 4.
+/// Unknown bits are ignored. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_set_enabled_buttons(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+    buttons: u32,
+) {
+    send_window_command(
+        event_loop,
+        window_ref,
+        WindowCommand::SetEnabledButtons(WindowButtons::from_bits_truncate(buttons)),
+    )
+    .log();
+}
+
+/// Minimize the window or restore it from the minimized state.
+/// Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_set_minimized(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+    minimized: bool,
+) {
+    send_window_command(
+        event_loop,
+        window_ref,
+        WindowCommand::SetMinimized(minimized),
+    )
+    .log();
+}
+
+/// Show or hide the window. A window built with `with_visibility(false)`
+/// can be shown once its first frame is rendered. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_set_visible(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+    visible: bool,
+) {
+    send_window_command(event_loop, window_ref, WindowCommand::SetVisible(visible)).log();
+}
+
+/// Set the size the window is resized in steps of, in physical pixels. Only X11 and macOS
+/// support it. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_set_resize_increments(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+    width: u32,
+    height: u32,
+) {
+    send_window_command(
+        event_loop,
+        window_ref,
+        WindowCommand::SetResizeIncrements(Some(PhysicalSize::new(width, height).into())),
+    )
+    .log();
+}
+
+/// Set the size the window is resized in steps of, in logical pixels. Only X11 and macOS
+/// support it. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_set_logical_resize_increments(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+    width: f64,
+    height: f64,
+) {
+    send_window_command(
+        event_loop,
+        window_ref,
+        WindowCommand::SetResizeIncrements(Some(LogicalSize::new(width, height).into())),
+    )
+    .log();
+}
+
+/// Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_clear_resize_increments(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+) {
+    send_window_command(
+        event_loop,
+        window_ref,
+        WindowCommand::SetResizeIncrements(None),
+    )
+    .log();
+}
+
+/// Keep the aspect ratio of the inner size of the window when it is resized by the user.
+/// The window is resized right away to match it. Does not apply to maximized and fullscreen
+/// windows. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_set_aspect_ratio(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+    width: f64,
+    height: f64,
+) {
+    let aspect_ratio = width / height;
+    Some(aspect_ratio)
+        .filter(|aspect_ratio| aspect_ratio.is_normal() && *aspect_ratio > 0.0)
+        .ok_or_else(|| WinitError::InvalidAspectRatio(aspect_ratio).boxed().into())
+        .and_then(|aspect_ratio| {
+            send_window_command(
+                event_loop,
+                window_ref,
+                WindowCommand::SetAspectRatio(Some(aspect_ratio)),
+            )
+        })
+        .log();
+}
+
+/// Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_clear_aspect_ratio(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+) {
+    send_window_command(event_loop, window_ref, WindowCommand::SetAspectRatio(None)).log();
+}
+
+/// Return the enforced aspect ratio (width / height) or 0 if there is none.
+/// Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_get_aspect_ratio(window_ref: *mut ValueBox<WindowRef>) -> f64 {
+    window_ref
+        .with_ref(|window_ref| {
+            window_ref
+                .aspect_ratio()
+                .map(|aspect_ratio| aspect_ratio.unwrap_or(0.0))
+                .map_err(|err| err.boxed().into())
+        })
+        .or_log(0.0)
+}
+
+/// Enter borderless fullscreen on a given monitor or on the current monitor
+/// if the monitor is null. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_set_borderless_fullscreen(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+    monitor: *mut ValueBox<MonitorHandle>,
+) {
+    borderless_fullscreen(monitor)
+        .and_then(|fullscreen| {
+            send_window_command(
+                event_loop,
+                window_ref,
+                WindowCommand::SetFullscreen(Some(fullscreen)),
+            )
+        })
+        .log();
+}
+
+/// Enter exclusive fullscreen with a given video mode. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_set_exclusive_fullscreen(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+    video_mode: *mut ValueBox<VideoMode>,
+) {
+    exclusive_fullscreen(video_mode)
+        .and_then(|fullscreen| {
+            send_window_command(
+                event_loop,
+                window_ref,
+                WindowCommand::SetFullscreen(Some(fullscreen)),
+            )
+        })
+        .log();
+}
+
+/// Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_exit_fullscreen(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+) {
+    send_window_command(event_loop, window_ref, WindowCommand::SetFullscreen(None)).log();
+}
+
+/// Get the fullscreen state of the window. Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_get_fullscreen(
+    window_ref: *mut ValueBox<WindowRef>,
+) -> WinitFullscreen {
+    window_ref
+        .with_ref(|window_ref| {
+            window_ref
+                .fullscreen()
+                .map(|fullscreen| fullscreen.as_ref().into())
+                .map_err(|err| err.boxed().into())
+        })
+        .or_log(WinitFullscreen::Windowed)
+}
+
+/// Can be called from any thread.
+#[no_mangle]
+pub extern "C" fn winit_window_ref_focus_window(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+) {
+    send_window_command(event_loop, window_ref, WindowCommand::Focus).log();
+}
+
 #[cfg(target_os = "macos")]
 #[no_mangle]
 pub extern "C" fn winit_window_ref_get_ns_view(
@@ -334,6 +1273,28 @@ pub extern "C" fn winit_window_ref_get_xlib_window(
     .or_log(0)
 }
 
+/// Return true if the urgency hint is set on the X11 window, which is how user attention
+/// requests are shown on X11. Must only be called from the main thread
+#[cfg(x11_platform)]
+#[no_mangle]
+pub extern "C" fn winit_window_ref_is_x11_urgent(
+    event_loop: *mut ValueBox<PollingEventLoop>,
+    window_ref: *mut ValueBox<WindowRef>,
+) -> bool {
+    with_window(event_loop, window_ref, |window, event_loop| {
+        crate::x11::is_urgent(window).map_err(|error| {
+            format!(
+                "Window (id: {:?}, type: {:?}) {}",
+                window.id(),
+                event_loop.get_type(),
+                error
+            )
+            .into()
+        })
+    })
+    .or_log(false)
+}
+
 #[cfg(wayland_platform)]
 #[no_mangle]
 pub extern "C" fn winit_window_ref_get_wayland_surface(
@@ -386,19 +1347,18 @@ pub extern "C" fn winit_window_ref_current_monitor(
     .or_log(std::ptr::null_mut())
 }
 
+/// Release the reference without destroying the window
+#[no_mangle]
+pub extern "C" fn winit_window_ref_drop(window_ref: *mut ValueBox<WindowRef>) {
+    window_ref.release();
+}
+
+/// Close the window and release the reference. Can be called from any thread.
 #[no_mangle]
 pub extern "C" fn winit_window_ref_destroy(
     event_loop: *mut ValueBox<PollingEventLoop>,
     window_ref: *mut ValueBox<WindowRef>,
 ) {
-    event_loop
-        .with_mut(|event_loop| {
-            window_ref.with_ref(|window_ref| {
-                event_loop
-                    .destroy_window(&window_ref.id())
-                    .map_err(|error| error.boxed().into())
-            })
-        })
-        .log();
+    send_window_command(event_loop, window_ref, WindowCommand::Destroy).log();
     window_ref.release();
 }
@@ -0,0 +1,39 @@
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+
+/// Thickness of the window decorations around the client area
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+#[repr(C)]
+pub struct FrameInsets<T> {
+    pub top: T,
+    pub left: T,
+    pub bottom: T,
+    pub right: T,
+}
+
+impl FrameInsets<i32> {
+    /// Compute the insets from the outer frame and the client area of a window
+    pub fn between(
+        outer_position: PhysicalPosition<i32>,
+        outer_size: PhysicalSize<u32>,
+        inner_position: PhysicalPosition<i32>,
+        inner_size: PhysicalSize<u32>,
+    ) -> Self {
+        let top = inner_position.y - outer_position.y;
+        let left = inner_position.x - outer_position.x;
+        Self {
+            top,
+            left,
+            bottom: outer_size.height as i32 - inner_size.height as i32 - top,
+            right: outer_size.width as i32 - inner_size.width as i32 - left,
+        }
+    }
+
+    pub fn to_logical(&self, scale_factor: f64) -> FrameInsets<f64> {
+        FrameInsets {
+            top: self.top as f64 / scale_factor,
+            left: self.left as f64 / scale_factor,
+            bottom: self.bottom as f64 / scale_factor,
+            right: self.right as f64 / scale_factor,
+        }
+    }
+}
@@ -10,8 +10,6 @@ extern crate phlow_extensions;
 #[macro_use]
 extern crate value_box;
 
-use std::mem::transmute_copy;
-
 use geometry_box::U128Box;
 #[cfg(feature = "phlow")]
 use phlow_extensions::CoreExtensions;
@@ -27,34 +25,44 @@ pub use raw_window_handle_extensions::*;
 pub use value_box_ffi::*;
 use winit::window::WindowId;
 
-pub use enums::{WinitCursorIcon, WinitUserEvent};
+pub use custom_cursor::{CursorImage, CustomCursor};
+pub use enums::{
+    WinitConfigFormat, WinitConfigProblemKind, WinitCursorGrabMode, WinitCursorIcon,
+    WinitFullscreen, WinitParentRelation, WinitResizeDirection, WinitTheme, WinitUserAttention,
+    WinitUserEvent, WinitWindowLevel, WinitX11WindowType,
+};
 pub use error::{Result, WinitError};
 pub use ffi::*;
+pub use frame_insets::FrameInsets;
 pub use polling_event_loop::*;
+pub use window_builder::WinitWindowBuilder;
+pub use window_command::WindowCommand;
+pub use window_config::{
+    ConfigProblem, MacOSConfig, WaylandConfig, WindowConfig, WindowConfigError, X11Config,
+};
+pub use window_ids::WindowIds;
 pub use window_ref::WindowRef;
 
+mod custom_cursor;
 mod enums;
 mod error;
 mod event_loop;
 
-#[cfg(any(
-    target_os = "windows",
-    target_os = "macos",
-    target_os = "android",
-    target_os = "linux",
-    target_os = "dragonfly",
-    target_os = "freebsd",
-    target_os = "netbsd",
-    target_os = "openbsd"
-))]
+#[cfg(run_return_platform)]
 mod event_loop_run_return;
 mod events;
 mod ffi;
+mod frame_insets;
 mod monitor;
 mod polling_event_loop;
 mod window;
 mod window_builder;
+mod window_command;
+mod window_config;
+mod window_ids;
 mod window_ref;
+#[cfg(x11_platform)]
+mod x11;
 
 #[cfg(feature = "phlow")]
 import_extensions!(CoreExtensions);
@@ -87,32 +95,9 @@ pub extern "C" fn winit_print(message: *mut ValueBox<StringBox>) {
         .log();
 }
 
+/// Convert a window id of a window that was created outside of the [`PollingEventLoop`]
 pub fn winit_convert_window_id(window_id: WindowId) -> U128Box {
-    let size = std::mem::size_of::<WindowId>();
-
-    let id_128: u128 = match size {
-        4 => {
-            // u32
-            let id: u32 = unsafe { transmute_copy::<WindowId, u32>(&window_id) };
-            id as u128
-        }
-        8 => {
-            // u64
-            let id: u64 = unsafe { transmute_copy::<WindowId, u64>(&window_id) };
-            id as u128
-        }
-        16 => {
-            //u128
-            let id: u128 = unsafe { transmute_copy::<WindowId, u128>(&window_id) };
-            id
-        }
-        _ => {
-            eprintln!("Unknown size of window id ({:?})", window_id);
-            0 as u128
-        }
-    };
-
-    id_128.into()
+    (u64::from(window_id) as u128).into()
 }
 
 #[no_mangle]
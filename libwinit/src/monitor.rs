@@ -1,6 +1,19 @@
 use geometry_box::SizeBox;
-use value_box::{ReturnBoxerResult, ValueBox, ValueBoxPointer};
-use winit::monitor::MonitorHandle;
+use value_box::{Result, ReturnBoxerResult, ValueBox, ValueBoxPointer};
+use winit::monitor::{MonitorHandle, VideoMode};
+use winit::window::Fullscreen;
+
+/// Borderless fullscreen on a given monitor or on the current monitor if the pointer is null
+pub(crate) fn borderless_fullscreen(monitor: *mut ValueBox<MonitorHandle>) -> Result<Fullscreen> {
+    if monitor.is_null() {
+        return Ok(Fullscreen::Borderless(None));
+    }
+    monitor.with_ref_ok(|monitor| Fullscreen::Borderless(Some(monitor.clone())))
+}
+
+pub(crate) fn exclusive_fullscreen(video_mode: *mut ValueBox<VideoMode>) -> Result<Fullscreen> {
+    video_mode.with_ref_ok(|video_mode| Fullscreen::Exclusive(video_mode.clone()))
+}
 
 #[no_mangle]
 pub extern "C" fn winit_monitor_get_hidpi_factor(
@@ -27,7 +40,75 @@ pub extern "C" fn winit_monitor_get_size(
         .log();
 }
 
+#[no_mangle]
+pub extern "C" fn winit_monitor_video_mode_count(
+    monitor_handle: *mut ValueBox<MonitorHandle>,
+) -> usize {
+    monitor_handle
+        .with_ref_ok(|monitor_handle| monitor_handle.video_modes().count())
+        .or_log(0)
+}
+
+/// Return a video mode supported by the monitor at a given index (starting from 0)
+/// or null if the index is out of bounds
+#[no_mangle]
+pub extern "C" fn winit_monitor_get_video_mode_at(
+    monitor_handle: *mut ValueBox<MonitorHandle>,
+    index: usize,
+) -> *mut ValueBox<VideoMode> {
+    monitor_handle
+        .with_ref_ok(|monitor_handle| {
+            monitor_handle
+                .video_modes()
+                .nth(index)
+                .map(|video_mode| ValueBox::new(video_mode).into_raw())
+                .unwrap_or(std::ptr::null_mut())
+        })
+        .or_log(std::ptr::null_mut())
+}
+
 #[no_mangle]
 pub extern "C" fn winit_monitor_drop(ptr: *mut ValueBox<MonitorHandle>) {
     ptr.release();
 }
+
+///////////////////////////////////////////////////////////////////////////////////////
+/////////////////////////////// V I D E O    M O D E //////////////////////////////////
+///////////////////////////////////////////////////////////////////////////////////////
+
+#[no_mangle]
+pub extern "C" fn winit_video_mode_get_size(
+    video_mode: *mut ValueBox<VideoMode>,
+    size: *mut ValueBox<SizeBox<u32>>,
+) {
+    video_mode
+        .with_ref(|video_mode| {
+            size.with_mut_ok(|size| {
+                let video_mode_size = video_mode.size();
+                size.width = video_mode_size.width;
+                size.height = video_mode_size.height;
+            })
+        })
+        .log();
+}
+
+#[no_mangle]
+pub extern "C" fn winit_video_mode_get_bit_depth(video_mode: *mut ValueBox<VideoMode>) -> u16 {
+    video_mode
+        .with_ref_ok(|video_mode| video_mode.bit_depth())
+        .or_log(0)
+}
+
+#[no_mangle]
+pub extern "C" fn winit_video_mode_get_refresh_rate_millihertz(
+    video_mode: *mut ValueBox<VideoMode>,
+) -> u32 {
+    video_mode
+        .with_ref_ok(|video_mode| video_mode.refresh_rate_millihertz())
+        .or_log(0)
+}
+
+#[no_mangle]
+pub extern "C" fn winit_video_mode_drop(ptr: *mut ValueBox<VideoMode>) {
+    ptr.release();
+}
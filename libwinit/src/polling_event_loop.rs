@@ -1,14 +1,21 @@
 use std::borrow::{Borrow, BorrowMut};
 use std::collections::{HashMap, VecDeque};
 use std::ffi::c_void;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Sender};
 use std::sync::Arc;
+use std::thread::ThreadId;
+#[cfg(run_return_platform)]
+use std::time::{Duration, Instant};
 
 use geometry_box::U128Box;
 use parking_lot::Mutex;
+use raw_window_handle::HasRawWindowHandle;
 use value_box::{BoxerError, ReturnBoxerResult};
-use winit::dpi::PhysicalSize;
+use winit::dpi::{PhysicalPosition, PhysicalSize};
 use winit::event::{Event, Ime, WindowEvent};
 use winit::event_loop::{ControlFlow, EventLoop, EventLoopProxy, EventLoopWindowTarget};
+use winit::monitor::MonitorHandle;
 #[cfg(android_platform)]
 use winit::platform::android::EventLoopBuilderExtAndroid;
 #[cfg(target_os = "ios")]
@@ -17,17 +24,30 @@ use winit::platform::ios::WindowBuilderExtIOS;
 use winit::platform::macos::EventLoopBuilderExtMacOS;
 #[cfg(windows_platform)]
 use winit::platform::windows::EventLoopBuilderExtWindows;
-use winit::window::{Window, WindowBuilder, WindowId};
+use winit::window::{CursorGrabMode, Window, WindowId};
 
 use crate::event_loop::{WinitEventLoopBuilder, WinitEventLoopType};
 use crate::events::{
     winit_event_loop_process_received_character, EventProcessor, WinitEvent, WinitEventType,
 };
-use crate::{winit_convert_window_id, Result, WindowRef, WinitError, WinitUserEvent};
+use crate::window_command::constrain_aspect_ratio;
+use crate::{
+    Result, WindowCommand, WindowIds, WindowRef, WinitError, WinitUserEvent, WinitWindowBuilder,
+};
 
 pub type WinitEventLoop = EventLoop<WinitUserEvent>;
 pub type WinitEventLoopProxy = EventLoopProxy<WinitUserEvent>;
 
+/// There are no events for the size of window decorations, so they are queried
+/// whenever the window moves or resizes
+fn update_window_frame(window: &Window, window_ref: &WindowRef) -> Result<()> {
+    window_ref.set_outer_size(window.outer_size())?;
+    match window.inner_position() {
+        Ok(position) => window_ref.set_inner_position(position),
+        Err(_) => window_ref.set_inner_position(window_ref.outer_position()?),
+    }
+}
+
 #[derive(Debug)]
 pub struct SemaphoreSignaller {
     semaphore_callback: unsafe extern "C" fn(usize, *const c_void),
@@ -114,15 +134,95 @@ impl WindowResizedListener {
     }
 }
 
+/// A host callback that is dispatched to run on the event loop thread
+#[derive(Debug)]
+pub struct DispatchedCallback {
+    callback: unsafe extern "C" fn(*const c_void),
+    thunk: *const c_void,
+    completion: DispatchCompletion,
+}
+
+#[derive(Debug)]
+enum DispatchCompletion {
+    None,
+    Signal(Sender<()>),
+    Callback {
+        callback: unsafe extern "C" fn(*const c_void),
+        thunk: *const c_void,
+    },
+}
+
+impl DispatchedCallback {
+    pub fn new(callback: unsafe extern "C" fn(*const c_void), thunk: *const c_void) -> Self {
+        Self {
+            callback,
+            thunk,
+            completion: DispatchCompletion::None,
+        }
+    }
+
+    /// Call a given callback on the event loop thread once the dispatched callback has run
+    pub fn with_completion_callback(
+        mut self,
+        callback: unsafe extern "C" fn(*const c_void),
+        thunk: *const c_void,
+    ) -> Self {
+        self.completion = DispatchCompletion::Callback { callback, thunk };
+        self
+    }
+
+    fn with_completion_signal(mut self, signal: Sender<()>) -> Self {
+        self.completion = DispatchCompletion::Signal(signal);
+        self
+    }
+
+    fn run(self) {
+        unsafe { (self.callback)(self.thunk) };
+
+        match self.completion {
+            DispatchCompletion::None => {}
+            DispatchCompletion::Signal(signal) => {
+                // the waiting thread may have given up already
+                signal.send(()).ok();
+            }
+            DispatchCompletion::Callback { callback, thunk } => unsafe { callback(thunk) },
+        }
+    }
+}
+
+/// Work queued from other threads to be done on the event loop thread
+#[derive(Debug)]
+enum EventLoopTask {
+    Dispatch(DispatchedCallback),
+    WindowCommand {
+        window_id: WindowId,
+        command: WindowCommand,
+        /// Restores the cached state of the window reference if the command fails
+        restore_command: Option<WindowCommand>,
+        /// The result is sent back when a caller waits for the command to be applied
+        result: Option<Sender<Result<()>>>,
+    },
+}
+
 #[derive(Debug)]
 pub struct PollingEventLoop {
     windows: Mutex<HashMap<WindowId, (WindowRef, Window)>>,
+    window_ids: Mutex<WindowIds>,
     events: Mutex<VecDeque<WinitEvent>>,
     pub(crate) event_loop_waker: WinitEventLoopWaker,
     semaphore_signaller: Option<SemaphoreSignaller>,
     main_events_cleared_signallers: Mutex<Vec<MainEventClearedSignaller>>,
     window_redraw_listeners: Mutex<HashMap<WindowId, WindowRedrawRequestedListener>>,
     window_resize_listeners: Mutex<HashMap<WindowId, WindowResizedListener>>,
+    exit_code: Mutex<Option<i32>>,
+    tasks: Mutex<VecDeque<EventLoopTask>>,
+    /// Set once the exit is processed, after which nobody runs the queued tasks.
+    /// Only changed while holding the `tasks` lock.
+    has_exited: AtomicBool,
+    event_loop: Option<WinitEventLoop>,
+    event_loop_thread: Option<ThreadId>,
+    event_processor: EventProcessor,
+    is_resumed: bool,
     pub(crate) running_event_loop: *const EventLoopWindowTarget<WinitUserEvent>,
     #[cfg(target_os = "android")]
     android_app: Option<winit::platform::android::activity::AndroidApp>,
@@ -132,12 +232,20 @@ impl PollingEventLoop {
     pub fn new() -> Self {
         Self {
             windows: Default::default(),
+            window_ids: Default::default(),
             events: Mutex::new(VecDeque::new()),
             event_loop_waker: WinitEventLoopWaker::new(),
             semaphore_signaller: None,
             main_events_cleared_signallers: Default::default(),
             window_redraw_listeners: Default::default(),
             window_resize_listeners: Default::default(),
+            exit_code: Default::default(),
+            tasks: Default::default(),
+            has_exited: AtomicBool::new(false),
+            event_loop: None,
+            event_loop_thread: None,
+            event_processor: EventProcessor::new(),
+            is_resumed: false,
             running_event_loop: std::ptr::null(),
             #[cfg(target_os = "android")]
             android_app: None,
@@ -255,14 +363,39 @@ impl PollingEventLoop {
 
     /// Is called when a window is resized
     fn on_window_resized(&mut self, window_id: &WindowId, size: &PhysicalSize<u32>) -> Result<()> {
+        // there is no dedicated event for (un)minimizing, (un)maximizing a window
+        // or changing its fullscreen state, but all of them change its size
+        self.with_window_mut(window_id, |window, window_ref| {
+            let is_empty = size.width == 0 && size.height == 0;
+            window_ref.set_minimized(window.is_minimized().unwrap_or(is_empty))?;
+            window_ref.set_maximized(window.is_maximized())?;
+            window_ref.set_fullscreen(window.fullscreen())
+        })?;
+
         // (Windows) when a window is minimized, its size is set to 0x0,
         // while it shouldn't actually change, so we just ignore the event
         if size.width == 0 && size.height == 0 {
             return Ok(());
         }
 
-        self.with_window_mut(window_id, |_window, window_ref| {
-            window_ref.set_inner_size(size.clone())
+        self.with_window_mut(window_id, |window, window_ref| {
+            let previous_size = window_ref.inner_size()?;
+            window_ref.set_inner_size(*size)?;
+            update_window_frame(window, window_ref)?;
+
+            // the size of maximized and fullscreen windows is given by the platform
+            let is_constrained = !window_ref.is_maximized()? && window_ref.fullscreen()?.is_none();
+            if let Some(aspect_ratio) = window_ref.aspect_ratio()?.filter(|_| is_constrained) {
+                let constrained_size = constrain_aspect_ratio(*size, previous_size, aspect_ratio);
+                // sizes are rounded to whole pixels, so a pixel off must not be corrected,
+                // otherwise the correction itself would trigger another one
+                if constrained_size.width.abs_diff(size.width) > 1
+                    || constrained_size.height.abs_diff(size.height) > 1
+                {
+                    window.set_inner_size(constrained_size);
+                }
+            }
+            Ok(())
         })?;
 
         if let Some(listener) = self.window_resize_listeners.lock().get(window_id) {
@@ -273,20 +406,77 @@ impl PollingEventLoop {
         Ok(())
     }
 
+    /// Is called when a window is moved
+    fn on_window_moved(
+        &mut self,
+        window_id: &WindowId,
+        position: &PhysicalPosition<i32>,
+    ) -> Result<()> {
+        self.with_window_mut(window_id, |window, window_ref| {
+            window_ref.set_outer_position(*position)?;
+            window_ref.set_minimized(window.is_minimized().unwrap_or(false))?;
+            update_window_frame(window, window_ref)
+        })
+    }
+
+    /// Is called when a window gains or loses focus.
+    /// Releases the cursor grab while the window is not focused and restores it afterwards
+    fn on_window_focused(&mut self, window_id: &WindowId, is_focused: bool) -> Result<()> {
+        self.with_window_mut(window_id, |window, window_ref| {
+            window_ref.set_focused(is_focused)?;
+            let cursor_grab = window_ref.cursor_grab()?;
+            if cursor_grab != CursorGrabMode::None {
+                let mode = if is_focused {
+                    cursor_grab
+                } else {
+                    CursorGrabMode::None
+                };
+                if let Err(error) = window.set_cursor_grab(mode) {
+                    // the platform refused the grab, so there is nothing to restore later
+                    window_ref.set_cursor_grab(CursorGrabMode::None)?;
+                    return Err(error.into());
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Keep the cached state of the window reference up-to-date
+    fn update_window_ref(
+        &mut self,
+        window_id: &WindowId,
+        callback: impl FnOnce(&WindowRef) -> Result<()>,
+    ) -> Result<()> {
+        self.with_window_mut(window_id, |_window, window_ref| callback(window_ref))
+    }
+
     /// Is called when window's scale changed
     fn on_window_scale_changed(
         &mut self,
         window_id: &WindowId,
         scale_factor: &f64,
-        new_inner_size: &PhysicalSize<u32>,
+        new_inner_size: &mut PhysicalSize<u32>,
     ) -> Result<()> {
-        self.with_window_mut(window_id, |_window, window_ref| {
-            window_ref.set_inner_size(new_inner_size.clone())?;
-            window_ref.set_scale_factor(scale_factor.clone())
+        self.with_window_mut(window_id, |window, window_ref| {
+            // the platform resizes the window to the size that is left in the event
+            let is_constrained = !window_ref.is_maximized()? && window_ref.fullscreen()?.is_none();
+            if let Some(aspect_ratio) = window_ref.aspect_ratio()?.filter(|_| is_constrained) {
+                *new_inner_size =
+                    constrain_aspect_ratio(*new_inner_size, window_ref.inner_size()?, aspect_ratio);
+            }
+            window_ref.set_inner_size(*new_inner_size)?;
+            window_ref.set_scale_factor(*scale_factor)?;
+            // the image of a custom cursor is picked for the scale factor
+            if let Some(cursor) = window_ref.custom_cursor()? {
+                if let Err(error) = cursor.apply(window) {
+                    warn!("Could not update the custom cursor: {}", error);
+                }
+            }
+            update_window_frame(window, window_ref)
         })?;
 
         if let Some(listeners) = self.window_resize_listeners.lock().get(window_id) {
-            listeners.on_window_resized(&new_inner_size);
+            listeners.on_window_resized(new_inner_size);
         }
 
         self.with_window(window_id, |window| Ok(window.request_redraw()))?;
@@ -294,16 +484,32 @@ impl PollingEventLoop {
     }
 
     /// Create and register a window in the event loop
-    pub fn create_window(&mut self, window_builder: WindowBuilder) -> Result<WindowRef> {
+    pub fn create_window(&mut self, window_builder: WinitWindowBuilder) -> Result<WindowRef> {
+        let window_attributes = window_builder.window_builder().window_attributes();
+        let window_level = window_attributes.window_level;
+        let is_transparent = window_attributes.transparent;
+        let ime_allowed = window_builder.ime_allowed();
+        let window_builder = match window_builder.parent() {
+            Some((parent_id, _)) => {
+                let parent_window_handle =
+                    self.with_window(&parent_id, |parent| Ok(parent.raw_window_handle()))?;
+                // the handle belongs to a window that is alive on the event loop thread
+                unsafe { window_builder.with_parent_window_handle(parent_window_handle) }
+            }
+            None => window_builder,
+        };
+
         self.event_loop()
             .ok_or(WinitError::EventLoopNotRunning)
             .and_then(|event_loop| {
                 (if let Some(_monitor) = event_loop.primary_monitor() {
                     #[cfg(target_os = "ios")]
                     {
-                        window_builder
-                            .with_inner_size(_monitor.size())
-                            .with_scale_factor(_monitor.scale_factor())
+                        window_builder.map(|window_builder| {
+                            window_builder
+                                .with_inner_size(_monitor.size())
+                                .with_scale_factor(_monitor.scale_factor())
+                        })
                     }
                     #[cfg(not(target_os = "ios"))]
                     {
@@ -313,19 +519,33 @@ impl PollingEventLoop {
                     window_builder
                 })
                 .build(event_loop)
-                .map_err(|err| err.into())
             })
             .and_then(|window| {
                 let window_id = window.id();
-                let window_ref = WindowRef::new(&window_id);
+                let host_id = self.window_ids.lock().register(window_id);
+                let window_ref = WindowRef::new(&window_id, host_id);
                 window_ref.set_scale_factor(window.scale_factor())?;
                 window_ref.set_inner_size(window.inner_size())?;
                 if let Ok(position) = window.outer_position() {
                     window_ref.set_outer_position(position)?;
                 }
-
-                window.set_ime_allowed(true);
-
+                update_window_frame(&window, &window_ref)?;
+                window_ref.set_focused(window.has_focus())?;
+                window_ref.set_minimized(window.is_minimized().unwrap_or(false))?;
+                window_ref.set_maximized(window.is_maximized())?;
+                window_ref.set_fullscreen(window.fullscreen())?;
+                window_ref.set_visible(window.is_visible().unwrap_or(true))?;
+                window_ref.set_decorated(window.is_decorated())?;
+                window_ref.set_resizable(window.is_resizable())?;
+                window_ref.set_transparent(is_transparent)?;
+                window_ref.set_window_level(window_level)?;
+                window_ref.set_enabled_buttons(window.enabled_buttons())?;
+                window_ref.set_theme(window.theme())?;
+                window_ref.set_title(window.title())?;
+                window_ref.set_ime_allowed(ime_allowed)?;
+
+                #[cfg(x11_platform)]
+                crate::x11::retain_display(&window);
                 self.windows
                     .lock()
                     .insert(window_id, (window_ref.clone(), window));
@@ -334,6 +554,55 @@ impl PollingEventLoop {
             })
     }
 
+    /// Must be called from the event loop thread while the event loop is running
+    pub fn available_monitors(&self) -> Result<Vec<MonitorHandle>> {
+        self.event_loop()
+            .ok_or(WinitError::EventLoopNotRunning)
+            .map(|event_loop| event_loop.available_monitors().collect())
+    }
+
+    /// Must be called from the event loop thread while the event loop is running
+    pub fn primary_monitor(&self) -> Result<Option<MonitorHandle>> {
+        self.event_loop()
+            .ok_or(WinitError::EventLoopNotRunning)
+            .map(|event_loop| event_loop.primary_monitor())
+    }
+
+    pub fn window_count(&self) -> usize {
+        self.windows.lock().len()
+    }
+
+    /// Return references to all live windows
+    pub fn window_refs(&self) -> Vec<WindowRef> {
+        self.windows
+            .lock()
+            .values()
+            .map(|(window_ref, _window)| window_ref.clone())
+            .collect()
+    }
+
+    /// Find a window by the id that is sent to the host as part of [`WinitEvent`]
+    pub fn find_window(&self, id: &U128Box) -> Option<WindowRef> {
+        self.find_window_id(id).and_then(|window_id| {
+            self.windows
+                .lock()
+                .get(&window_id)
+                .map(|(window_ref, _window)| window_ref.clone())
+        })
+    }
+
+    /// Convert the id that is sent to the host to the winit window id
+    pub fn find_window_id(&self, id: &U128Box) -> Option<WindowId> {
+        self.window_ids.lock().window_id(id)
+    }
+
+    /// Convert the winit window id to the id that is sent to the host.
+    /// Unknown windows get `0`
+    pub fn host_window_id(&self, window_id: &WindowId) -> U128Box {
+        let id = self.window_ids.lock().id(window_id).unwrap_or(0);
+        (id as u128).into()
+    }
+
     pub fn with_window<T>(
         &self,
         window_id: &WindowId,
@@ -358,13 +627,18 @@ impl PollingEventLoop {
             .and_then(|(window_ref, window)| callback(window, window_ref))
     }
 
-    /// Destroy a window by its id. Removes all assigned resize and redraw listeners
-    pub fn destroy_window(&mut self, window_id: &WindowId) -> Result<()> {
+    /// Destroy a window by its id. Removes all assigned resize and redraw listeners.
+    /// Must be called from the event loop thread, use [`WindowCommand::Destroy`] otherwise
+    pub fn destroy_window(&self, window_id: &WindowId) -> Result<()> {
         self.window_resize_listeners.lock().remove(window_id);
         self.window_redraw_listeners.lock().remove(window_id);
 
-        if let Some(window) = self.windows.lock().remove(window_id) {
+        let window = self.windows.lock().remove(window_id);
+        if let Some((_window_ref, window)) = window {
+            #[cfg(x11_platform)]
+            crate::x11::release_display(&window);
             drop(window);
+            self.unregister_window(window_id);
             info!("Closed window with id {:?}", window_id);
             Ok(())
         } else {
@@ -373,9 +647,220 @@ impl PollingEventLoop {
         }
     }
 
+    /// Destroy all registered windows together with their resize and redraw listeners
+    pub fn destroy_all_windows(&mut self) {
+        self.window_resize_listeners.lock().clear();
+        self.window_redraw_listeners.lock().clear();
+
+        let windows = std::mem::take(&mut *self.windows.lock());
+        info!("Closing {} window(s)", windows.len());
+        let window_ids = windows.keys().copied().collect::<Vec<_>>();
+        #[cfg(x11_platform)]
+        for (_window_ref, window) in windows.values() {
+            crate::x11::release_display(window);
+        }
+        drop(windows);
+        for window_id in window_ids {
+            self.unregister_window(&window_id);
+        }
+    }
+
+    /// Forget the id of a removed window and tell the host that the window is destroyed.
+    /// Platforms may send `Destroyed` for a dropped window only later or not at all
+    fn unregister_window(&self, window_id: &WindowId) {
+        let id = self.window_ids.lock().unregister(window_id);
+        if let Some(id) = id {
+            self.events.lock().push_back(WinitEvent {
+                window_id: (id as u128).into(),
+                event_type: WinitEventType::WindowEventDestroyed,
+                ..Default::default()
+            });
+            self.signal_semaphore();
+        }
+    }
+
+    /// Request the event loop to exit with a given exit code. Can be called from any thread.
+    /// All registered windows are destroyed and the host receives `LoopDestroyed`.
+    /// If the event loop is not running yet it will exit as soon as it is started.
+    pub fn request_exit(&self, exit_code: i32) -> Result<()> {
+        self.exit_code.lock().replace(exit_code);
+        self.wake(WinitUserEvent::Exit)
+    }
+
+    /// Queue a callback to be run on the event loop thread while the event loop is running,
+    /// which means that windows can be created and accessed from it. Can be called from any thread.
+    /// Fails once the event loop has exited.
+    pub fn dispatch(&self, callback: DispatchedCallback) -> Result<()> {
+        self.push_task(EventLoopTask::Dispatch(callback))
+    }
+
+    /// Run a callback on the event loop thread and wait until it finishes.
+    /// When called from the event loop thread the callback runs immediately.
+    /// Fails if the event loop was never started or has exited.
+    pub fn dispatch_blocking(&self, callback: DispatchedCallback) -> Result<()> {
+        if self.is_event_loop_thread() {
+            return if self.event_loop().is_some() {
+                callback.run();
+                Ok(())
+            } else {
+                // waiting would block the event loop forever
+                Err(WinitError::EventLoopNotRunning)
+            };
+        }
+        if self.event_loop_thread.is_none() {
+            // nobody would ever answer
+            return Err(WinitError::EventLoopNotRunning);
+        }
+
+        let (sender, receiver) = channel();
+        self.dispatch(callback.with_completion_signal(sender))?;
+        receiver.recv().map_err(|_| WinitError::DispatchCancelled)
+    }
+
+    /// Queue a task for the event loop thread unless the event loop has exited,
+    /// in which case nobody would ever run it
+    fn push_task(&self, task: EventLoopTask) -> Result<()> {
+        let mut tasks = self.tasks.lock();
+        if self.has_exited.load(Ordering::SeqCst) {
+            return Err(WinitError::EventLoopNotRunning);
+        }
+        tasks.push_back(task);
+        drop(tasks);
+        self.wake(WinitUserEvent::Dispatch)
+    }
+
+    /// Mark the event loop as exited and drop the pending tasks,
+    /// which releases the threads waiting for them
+    fn discard_tasks(&self) {
+        let mut tasks = self.tasks.lock();
+        self.has_exited.store(true, Ordering::SeqCst);
+        let discarded_tasks = std::mem::take(&mut *tasks);
+        drop(tasks);
+        drop(discarded_tasks);
+    }
+
+    fn is_event_loop_thread(&self) -> bool {
+        self.event_loop_thread
+            .map(|thread_id| thread_id == std::thread::current().id())
+            .unwrap_or(false)
+    }
+
+    /// Apply a command to a window on the event loop thread. The cached state of the
+    /// window reference is updated right away and restored if the command fails.
+    /// Can be called from any thread.
+    pub fn send_window_command(
+        &self,
+        window_ref: &WindowRef,
+        command: WindowCommand,
+    ) -> Result<()> {
+        let restore_command = window_ref.restore_command(&command)?;
+        window_ref.apply_command(&command)?;
+
+        if self.is_event_loop_thread() {
+            self.apply_window_command(&window_ref.id(), command, restore_command)
+        } else {
+            self.push_task(EventLoopTask::WindowCommand {
+                window_id: window_ref.id(),
+                command,
+                restore_command,
+                result: None,
+            })
+        }
+    }
+
+    /// Apply a command to a window on the event loop thread and wait for the result,
+    /// for commands that the platform may refuse. Can be called from any thread.
+    pub fn send_window_command_blocking(
+        &self,
+        window_ref: &WindowRef,
+        command: WindowCommand,
+    ) -> Result<()> {
+        if self.is_event_loop_thread() {
+            return self.send_window_command(window_ref, command);
+        }
+        if self.event_loop_thread.is_none() {
+            // nobody would ever answer
+            return Err(WinitError::EventLoopNotRunning);
+        }
+
+        let restore_command = window_ref.restore_command(&command)?;
+        window_ref.apply_command(&command)?;
+
+        let (sender, receiver) = channel();
+        self.push_task(EventLoopTask::WindowCommand {
+            window_id: window_ref.id(),
+            command,
+            restore_command,
+            result: Some(sender),
+        })?;
+        receiver
+            .recv()
+            .map_err(|_| WinitError::DispatchCancelled)
+            .and_then(|result| result)
+    }
+
+    /// Must be called from the event loop thread
+    fn apply_window_command(
+        &self,
+        window_id: &WindowId,
+        command: WindowCommand,
+        restore_command: Option<WindowCommand>,
+    ) -> Result<()> {
+        // the window must not be borrowed while it is dropped
+        if let WindowCommand::Destroy = command {
+            return self.destroy_window(window_id);
+        }
+
+        let windows = self.windows.lock();
+        let (window_ref, window) = windows
+            .get(window_id)
+            .ok_or_else(|| WinitError::WindowNotFound(*window_id))?;
+
+        command.apply(window).or_else(|error| {
+            if let Some(restore_command) = restore_command {
+                window_ref.apply_command(&restore_command)?;
+            }
+            Err(error)
+        })
+    }
+
+    fn run_tasks(&mut self) {
+        // the lock must not be held while running the tasks as they may queue more
+        let tasks = std::mem::take(&mut *self.tasks.lock());
+        for task in tasks {
+            match task {
+                EventLoopTask::Dispatch(callback) => callback.run(),
+                EventLoopTask::WindowCommand {
+                    window_id,
+                    command,
+                    restore_command,
+                    result,
+                } => {
+                    let applied = self.apply_window_command(&window_id, command, restore_command);
+                    match result {
+                        // the waiting thread may have given up already
+                        Some(result) => {
+                            result.send(applied).ok();
+                        }
+                        None => applied.map_err(BoxerError::from).log(),
+                    }
+                }
+            }
+        }
+    }
+
     pub fn run(&'static mut self) {
-        let mut event_processor = EventProcessor::new();
+        let event_loop = self
+            .event_loop
+            .take()
+            .unwrap_or_else(|| self.build_event_loop());
+
+        self.run_event_loop(event_loop);
+    }
 
+    /// The event loop can only be created once per application, which is why we keep it around
+    /// on platforms where it returns control to the caller.
+    fn build_event_loop(&mut self) -> WinitEventLoop {
         let mut event_loop_builder = WinitEventLoopBuilder::with_user_event();
         #[cfg(android_platform)]
         event_loop_builder.with_android_app(
@@ -394,73 +879,215 @@ impl PollingEventLoop {
 
         let event_loop = event_loop_builder.build();
         self.event_loop_waker.proxy(event_loop.create_proxy());
+        self.event_loop_thread = Some(std::thread::current().id());
+        event_loop
+    }
+
+    /// On X11 and Wayland the event loop returns to the caller once it exits.
+    #[cfg(any(x11_platform, wayland_platform))]
+    fn run_event_loop(&'static mut self, mut event_loop: WinitEventLoop) {
+        use winit::platform::run_return::EventLoopExtRunReturn;
+
+        self.has_exited.store(false, Ordering::SeqCst);
+        let exit_code = event_loop.run_return(|event, event_loop, control_flow| {
+            self.process_event(event, event_loop, control_flow)
+        });
+        info!("Event loop exited with code {}", exit_code);
+        // the event loop is kept, so waking it still succeeds even though nobody runs the tasks
+        self.discard_tasks();
+
+        self.event_loop = Some(event_loop);
+    }
+
+    /// On other platforms the process is terminated once the event loop exits.
+    #[cfg(not(any(x11_platform, wayland_platform)))]
+    fn run_event_loop(&'static mut self, event_loop: WinitEventLoop) {
+        event_loop.run(move |event, event_loop, control_flow| {
+            self.process_event(event, event_loop, control_flow)
+        })
+    }
+
+    /// Process pending events and return control to the caller, waiting at most `timeout`
+    /// for new events to arrive. The event loop is built on the first call.
+    /// Returns `false` once the event loop has exited.
+    #[cfg(run_return_platform)]
+    pub fn pump(&mut self, timeout: Duration) -> bool {
+        use winit::platform::run_return::EventLoopExtRunReturn;
+
+        let mut event_loop = match self.event_loop.take() {
+            Some(event_loop) => event_loop,
+            None => self.build_event_loop(),
+        };
+
+        // pumping again after an exit starts the event loop again
+        self.has_exited.store(false, Ordering::SeqCst);
+
+        let deadline = Instant::now() + timeout;
+        let mut has_events = false;
+        let mut is_waiting = false;
+        let mut is_exiting = false;
+
+        event_loop.run_return(|event, event_loop, control_flow| {
+            match &event {
+                Event::NewEvents(_)
+                | Event::MainEventsCleared
+                | Event::RedrawRequested(_)
+                | Event::RedrawEventsCleared => {}
+                // every call to `run_return` resumes and destroys the loop,
+                // the host should only know about it once
+                Event::Resumed => {
+                    if self.is_resumed {
+                        return;
+                    }
+                    self.is_resumed = true;
+                }
+                Event::Suspended => self.is_resumed = false,
+                Event::LoopDestroyed => {
+                    if !is_exiting {
+                        return;
+                    }
+                }
+                _ => has_events = true,
+            }
+
+            let is_iteration_end = matches!(event, Event::RedrawEventsCleared);
+            self.process_event(event, event_loop, control_flow);
+            // the exit may also be requested by a task dispatched while processing the event
+            is_exiting |= matches!(*control_flow, ControlFlow::ExitWithCode(_));
 
-        event_loop.run(move |event, event_loop, control_flow: &mut ControlFlow| {
-            self.running_event_loop = event_loop as *const EventLoopWindowTarget<WinitUserEvent>;
-            *control_flow = ControlFlow::Wait;
-
-            trace!("{:?}", &event);
-
-            let result = match &event {
-                Event::UserEvent(value) => Ok(debug!("Received UserEvent({:?})", value)),
-                Event::RedrawRequested(window_id) => self.on_redraw_requested(window_id),
-                Event::WindowEvent { window_id, event } => {
-                    match event {
-                        WindowEvent::Resized(size) => self.on_window_resized(window_id, size),
-                        WindowEvent::ScaleFactorChanged {
-                            scale_factor,
-                            new_inner_size,
-                        } => self.on_window_scale_changed(window_id, scale_factor, new_inner_size),
-                        WindowEvent::Ime(ime) => {
-                            match ime {
-                                Ime::Enabled => {}
-                                Ime::Preedit(_, _) => {}
-                                Ime::Commit(string) => {
-                                    for char in string.chars() {
-                                        let mut c_event = WinitEvent::default();
-                                        let id: U128Box =
-                                            winit_convert_window_id(window_id.clone());
-                                        c_event.window_id.clone_from(&id);
-                                        winit_event_loop_process_received_character(
-                                            &mut c_event,
-                                            char,
-                                        );
-                                        self.push(c_event);
-                                        self.signal_semaphore();
-                                    }
-                                }
-                                Ime::Disabled => {}
+            if is_iteration_end && !is_exiting {
+                if has_events || is_waiting || Instant::now() >= deadline {
+                    *control_flow = ControlFlow::Exit;
+                } else {
+                    *control_flow = ControlFlow::WaitUntil(deadline);
+                    is_waiting = true;
+                }
+            }
+        });
+
+        self.event_loop = Some(event_loop);
+        !is_exiting
+    }
+
+    fn process_event(
+        &mut self,
+        mut event: Event<WinitUserEvent>,
+        event_loop: &EventLoopWindowTarget<WinitUserEvent>,
+        control_flow: &mut ControlFlow,
+    ) {
+        self.running_event_loop = event_loop as *const EventLoopWindowTarget<WinitUserEvent>;
+        *control_flow = ControlFlow::Wait;
+
+        trace!("{:?}", &event);
+
+        if !self.tasks.lock().is_empty() {
+            self.run_tasks();
+        }
+
+        let result = match &mut event {
+            Event::UserEvent(value) => Ok(debug!("Received UserEvent({:?})", value)),
+            Event::RedrawRequested(window_id) => self.on_redraw_requested(window_id),
+            Event::WindowEvent { window_id, event } => match event {
+                WindowEvent::Resized(size) => self.on_window_resized(window_id, size),
+                WindowEvent::Moved(position) => self.on_window_moved(window_id, position),
+                WindowEvent::Focused(is_focused) => self.on_window_focused(window_id, *is_focused),
+                WindowEvent::Occluded(is_occluded) => self
+                    .update_window_ref(window_id, |window_ref| {
+                        window_ref.set_occluded(*is_occluded)
+                    }),
+                WindowEvent::ThemeChanged(theme) => self
+                    .update_window_ref(window_id, |window_ref| window_ref.set_theme(Some(*theme))),
+                WindowEvent::CursorEntered { .. } => {
+                    self.update_window_ref(window_id, |window_ref| window_ref.set_hovered(true))
+                }
+                WindowEvent::CursorLeft { .. } => {
+                    self.update_window_ref(window_id, |window_ref| window_ref.set_hovered(false))
+                }
+                WindowEvent::CursorMoved { position, .. } => self
+                    .update_window_ref(window_id, |window_ref| {
+                        window_ref.set_cursor_position(Some(*position))
+                    }),
+                WindowEvent::ScaleFactorChanged {
+                    scale_factor,
+                    new_inner_size,
+                } => self.on_window_scale_changed(window_id, scale_factor, new_inner_size),
+                WindowEvent::Ime(ime) => {
+                    match ime {
+                        Ime::Enabled => {}
+                        Ime::Preedit(_, _) => {}
+                        Ime::Commit(string) => {
+                            for char in string.chars() {
+                                let mut c_event = WinitEvent::default();
+                                let id: U128Box = self.host_window_id(window_id);
+                                c_event.window_id.clone_from(&id);
+                                winit_event_loop_process_received_character(&mut c_event, char);
+                                self.push(c_event);
+                                self.signal_semaphore();
                             }
-                            Ok(())
                         }
-                        _ => Ok(()),
+                        Ime::Disabled => {}
                     }
+                    Ok(())
                 }
                 _ => Ok(()),
-            };
-
-            result.map_err(|error| BoxerError::from(error)).log();
+            },
+            _ => Ok(()),
+        };
+
+        result.map_err(BoxerError::from).log();
+
+        let exit_code = self.exit_code.lock().take();
+        if let Some(exit_code) = exit_code {
+            info!("Exit requested with code {}", exit_code);
+            self.destroy_all_windows();
+            self.discard_tasks();
+            *control_flow = ControlFlow::ExitWithCode(exit_code);
+        }
 
-            let mut c_event = WinitEvent::default();
-            let processed = event_processor.process(event, &mut c_event);
-            if processed {
-                let event_type = c_event.event_type;
+        if let Event::UserEvent(user_event) = &event {
+            if user_event.is_internal() {
+                self.running_event_loop = std::ptr::null_mut();
+                return;
+            }
+        }
 
-                if event_type != WinitEventType::MainEventsCleared
-                    && event_type != WinitEventType::RedrawEventsCleared
-                    && event_type != WinitEventType::NewEvents
-                    && event_type != WinitEventType::RedrawRequested
-                {
-                    self.push(c_event);
-                    self.signal_semaphore();
-                }
+        let window_id = match &event {
+            Event::WindowEvent { window_id, .. } => Some(*window_id),
+            Event::RedrawRequested(window_id) => Some(*window_id),
+            _ => None,
+        };
+
+        let mut c_event = WinitEvent::default();
+        let processed = self.event_processor.process(event, &mut c_event);
+        if let Some(window_id) = window_id.as_ref() {
+            let id: U128Box = self.host_window_id(window_id);
+            c_event.window_id.clone_from(&id);
+        }
+        if processed {
+            let event_type = c_event.event_type;
+
+            // the host is told only once, the window may have been removed before
+            let is_already_destroyed = event_type == WinitEventType::WindowEventDestroyed
+                && window_id
+                    .as_ref()
+                    .and_then(|window_id| self.window_ids.lock().unregister(window_id))
+                    .is_none();
+
+            if event_type != WinitEventType::MainEventsCleared
+                && event_type != WinitEventType::RedrawEventsCleared
+                && event_type != WinitEventType::NewEvents
+                && event_type != WinitEventType::RedrawRequested
+                && !is_already_destroyed
+            {
+                self.push(c_event);
+                self.signal_semaphore();
+            }
 
-                if event_type == WinitEventType::MainEventsCleared {
-                    self.signal_main_events_cleared();
-                }
+            if event_type == WinitEventType::MainEventsCleared {
+                self.signal_main_events_cleared();
             }
-            self.running_event_loop = std::ptr::null_mut();
-        })
+        }
+        self.running_event_loop = std::ptr::null_mut();
     }
 
     pub fn wake(&self, event: WinitUserEvent) -> Result<()> {
@@ -18,16 +18,15 @@ use winit::platform::windows::WindowExtWindows;
 ))]
 use winit::platform::x11::WindowExtX11;
 use winit::window::Window;
-use winit::window::WindowBuilder;
 
-use crate::enums::WinitCursorIcon;
+use crate::enums::{WinitCursorGrabMode, WinitCursorIcon};
 use crate::event_loop::WinitEventLoop;
-use crate::{winit_convert_window_id, WinitError};
+use crate::{winit_convert_window_id, FrameInsets, WinitError, WinitWindowBuilder};
 
 #[no_mangle]
 pub extern "C" fn winit_create_window(
     event_loop: *mut ValueBox<WinitEventLoop>,
-    window_builder: *mut ValueBox<WindowBuilder>,
+    window_builder: *mut ValueBox<WinitWindowBuilder>,
 ) -> *mut ValueBox<Window> {
     event_loop
         .with_ref(|event_loop| {
@@ -36,7 +35,7 @@ pub extern "C" fn winit_create_window(
                 window_builder
                     .build(&event_loop)
                     .map(|window| value_box!(window))
-                    .map_err(|error| Into::<WinitError>::into(error).into())
+                    .map_err(|error| error.into())
             })
         })
         .into_raw()
@@ -123,6 +122,69 @@ pub extern "C" fn winit_window_get_position(
         .log();
 }
 
+#[no_mangle]
+pub extern "C" fn winit_window_get_outer_size(
+    window: *mut ValueBox<Window>,
+    size: *mut ValueBox<SizeBox<u32>>,
+) {
+    window
+        .with_ref(|window| {
+            size.with_mut_ok(|size| {
+                let outer_size = window.outer_size();
+                size.width = outer_size.width;
+                size.height = outer_size.height;
+            })
+        })
+        .log();
+}
+
+#[no_mangle]
+pub extern "C" fn winit_window_get_inner_position(
+    window: *mut ValueBox<Window>,
+    position: *mut ValueBox<PointBox<i32>>,
+) {
+    window
+        .with_ref(|window| {
+            position.with_mut(|position| {
+                window
+                    .inner_position()
+                    .map_err(|error| Into::<WinitError>::into(error).into())
+                    .map(|inner_position| {
+                        position.x = inner_position.x;
+                        position.y = inner_position.y;
+                    })
+            })
+        })
+        .log();
+}
+
+/// Platforms that can't tell the window position report no insets
+fn frame_insets(window: &Window) -> FrameInsets<i32> {
+    let outer_position = window.outer_position().unwrap_or_default();
+    FrameInsets::between(
+        outer_position,
+        window.outer_size(),
+        window.inner_position().unwrap_or(outer_position),
+        window.inner_size(),
+    )
+}
+
+/// Return the thickness of the window decorations in physical pixels
+#[no_mangle]
+pub extern "C" fn winit_window_get_frame_insets(window: *mut ValueBox<Window>) -> FrameInsets<i32> {
+    window.with_ref_ok(frame_insets).or_log(Default::default())
+}
+
+/// Return the thickness of the window decorations in logical pixels
+#[no_mangle]
+pub extern "C" fn winit_window_get_logical_frame_insets(
+    window: *mut ValueBox<Window>,
+) -> FrameInsets<f64> {
+    window
+        .with_ref_ok(|window| frame_insets(window).to_logical(window.scale_factor()))
+        .or_log(Default::default())
+}
+
 #[no_mangle]
 pub extern "C" fn winit_window_set_position(window: *mut ValueBox<Window>, x: i32, y: i32) {
     window
@@ -168,6 +230,29 @@ pub extern "C" fn winit_window_set_cursor_icon(
         .log();
 }
 
+/// Return true if the cursor was grabbed or released
+#[no_mangle]
+pub extern "C" fn winit_window_set_cursor_grab(
+    window: *mut ValueBox<Window>,
+    mode: WinitCursorGrabMode,
+) -> bool {
+    window
+        .with_ref(|window| {
+            window
+                .set_cursor_grab(mode.into())
+                .map_err(|error| Into::<WinitError>::into(error).into())
+        })
+        .map(|_| true)
+        .or_log(false)
+}
+
+#[no_mangle]
+pub extern "C" fn winit_window_set_cursor_visible(window: *mut ValueBox<Window>, visible: bool) {
+    window
+        .with_ref_ok(|window| window.set_cursor_visible(visible))
+        .log();
+}
+
 #[no_mangle]
 pub extern "C" fn winit_window_set_maximized(window: *mut ValueBox<Window>, maximized: bool) {
     window
@@ -175,6 +260,41 @@ pub extern "C" fn winit_window_set_maximized(window: *mut ValueBox<Window>, maxi
         .log();
 }
 
+#[no_mangle]
+pub extern "C" fn winit_window_is_maximized(window: *mut ValueBox<Window>) -> bool {
+    window.with_ref_ok(Window::is_maximized).or_log(false)
+}
+
+#[no_mangle]
+pub extern "C" fn winit_window_set_minimized(window: *mut ValueBox<Window>, minimized: bool) {
+    window
+        .with_ref_ok(|window| window.set_minimized(minimized))
+        .log();
+}
+
+/// Platforms that can't tell whether the window is minimized report `false`
+#[no_mangle]
+pub extern "C" fn winit_window_is_minimized(window: *mut ValueBox<Window>) -> bool {
+    window
+        .with_ref_ok(|window| window.is_minimized().unwrap_or(false))
+        .or_log(false)
+}
+
+#[no_mangle]
+pub extern "C" fn winit_window_set_visible(window: *mut ValueBox<Window>, visible: bool) {
+    window
+        .with_ref_ok(|window| window.set_visible(visible))
+        .log();
+}
+
+/// Platforms that can't tell whether the window is visible report `true`
+#[no_mangle]
+pub extern "C" fn winit_window_is_visible(window: *mut ValueBox<Window>) -> bool {
+    window
+        .with_ref_ok(|window| window.is_visible().unwrap_or(true))
+        .or_log(true)
+}
+
 #[no_mangle]
 pub extern "C" fn winit_window_focus_window(window: *mut ValueBox<Window>) {
     window.with_ref_ok(|window| window.focus_window()).log();
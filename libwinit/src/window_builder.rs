@@ -1,133 +1,755 @@
-use winit::dpi::LogicalSize;
+use std::path::{Path, PathBuf};
+
+use raw_window_handle::RawWindowHandle;
+use winit::dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize};
+use winit::event_loop::EventLoopWindowTarget;
+use winit::monitor::{MonitorHandle, VideoMode};
 #[cfg(target_os = "macos")]
 use winit::platform::macos::WindowBuilderExtMacOS;
-use winit::window::{WindowBuilder, WindowLevel};
+#[cfg(wayland_platform)]
+use winit::platform::wayland::{EventLoopWindowTargetExtWayland, WindowBuilderExtWayland};
+#[cfg(target_os = "windows")]
+use winit::platform::windows::WindowBuilderExtWindows;
+#[cfg(x11_platform)]
+use winit::platform::x11::{EventLoopWindowTargetExtX11, WindowBuilderExtX11};
+use winit::window::{Icon, Window, WindowBuilder, WindowId, WindowLevel};
 
 use string_box::StringBox;
 use value_box::{ReturnBoxerResult, ValueBox, ValueBoxPointer};
 
+use crate::enums::{WinitParentRelation, WinitX11WindowType};
+use crate::monitor::{borderless_fullscreen, exclusive_fullscreen};
+use crate::{Result, WindowRef, WinitError};
+
+/// Create an icon from RGBA8 pixels. A null buffer is treated as empty.
+/// # Safety
+/// `rgba` must point to at least `length` bytes
+pub(crate) unsafe fn icon_from_rgba(
+    width: u32,
+    height: u32,
+    rgba: *const u8,
+    length: usize,
+) -> Result<Icon> {
+    let rgba = if rgba.is_null() {
+        vec![]
+    } else {
+        std::slice::from_raw_parts(rgba, length).to_vec()
+    };
+    Icon::from_rgba(rgba, width, height).map_err(|error| error.into())
+}
+
+/// winit's window builder with the options that the library applies once the window is created
+#[derive(Debug, Clone)]
+pub struct WinitWindowBuilder {
+    window_builder: WindowBuilder,
+    ime_allowed: bool,
+    centered: bool,
+    x11_parent_window: Option<u64>,
+    parent: Option<(WindowId, WinitParentRelation)>,
+    parent_window_handle: Option<RawWindowHandle>,
+    x11_name: Option<(String, String)>,
+    x11_window_type: Option<WinitX11WindowType>,
+    x11_override_redirect: Option<bool>,
+    x11_base_size: Option<PhysicalSize<u32>>,
+    wayland_name: Option<(String, String)>,
+    macos_full_size: Option<bool>,
+    icon_path: Option<PathBuf>,
+}
+
+impl WinitWindowBuilder {
+    pub fn new() -> Self {
+        Self {
+            window_builder: WindowBuilder::new(),
+            ime_allowed: true,
+            centered: false,
+            x11_parent_window: None,
+            parent: None,
+            parent_window_handle: None,
+            x11_name: None,
+            x11_window_type: None,
+            x11_override_redirect: None,
+            x11_base_size: None,
+            wayland_name: None,
+            macos_full_size: None,
+            icon_path: None,
+        }
+    }
+
+    pub fn map(mut self, update: impl FnOnce(WindowBuilder) -> WindowBuilder) -> Self {
+        self.window_builder = update(self.window_builder);
+        self
+    }
+
+    pub fn with_ime_allowed(mut self, ime_allowed: bool) -> Self {
+        self.ime_allowed = ime_allowed;
+        self
+    }
+
+    /// Center the window on the primary monitor, which takes precedence over its position
+    pub fn with_centered(mut self, centered: bool) -> Self {
+        self.centered = centered;
+        self
+    }
+
+    /// Embed the window into a window of another application given by its XID.
+    /// Ignored on other platforms
+    pub fn with_x11_parent_window(mut self, parent_window: u64) -> Self {
+        self.x11_parent_window = Some(parent_window);
+        self
+    }
+
+    /// Create the window as a child or an owned window of another window of the event loop.
+    /// The parent window must stay open as long as the window exists.
+    pub fn with_parent(mut self, parent: WindowId, relation: WinitParentRelation) -> Self {
+        self.parent = Some((parent, relation));
+        self
+    }
+
+    /// Set the `WM_CLASS` of the window when it is created on X11
+    pub fn with_x11_name(
+        mut self,
+        general: impl Into<String>,
+        instance: impl Into<String>,
+    ) -> Self {
+        self.x11_name = Some((general.into(), instance.into()));
+        self
+    }
+
+    /// Set the `_NET_WM_WINDOW_TYPE` of the window. Ignored on other platforms
+    pub fn with_x11_window_type(mut self, window_type: WinitX11WindowType) -> Self {
+        self.x11_window_type = Some(window_type);
+        self
+    }
+
+    /// Let the window bypass the window manager. Ignored on other platforms
+    pub fn with_x11_override_redirect(mut self, override_redirect: bool) -> Self {
+        self.x11_override_redirect = Some(override_redirect);
+        self
+    }
+
+    /// Set the physical size the resize increments are counted from. Ignored on other platforms
+    pub fn with_x11_base_size(mut self, base_size: PhysicalSize<u32>) -> Self {
+        self.x11_base_size = Some(base_size);
+        self
+    }
+
+    /// Set the `app_id` of the window when it is created on Wayland
+    pub fn with_wayland_name(
+        mut self,
+        general: impl Into<String>,
+        instance: impl Into<String>,
+    ) -> Self {
+        self.wayland_name = Some((general.into(), instance.into()));
+        self
+    }
+
+    /// Extend the content under a transparent title bar. Ignored on other platforms
+    pub fn with_macos_full_size(mut self, full_size: bool) -> Self {
+        self.macos_full_size = Some(full_size);
+        self
+    }
+
+    pub fn with_window_icon(mut self, icon: Icon) -> Self {
+        self.window_builder = self.window_builder.with_window_icon(Some(icon));
+        self.icon_path = None;
+        self
+    }
+
+    /// Set an icon that was loaded from a file, remembering where it comes from
+    pub fn with_window_icon_file(mut self, icon: Icon, path: impl Into<PathBuf>) -> Self {
+        self.window_builder = self.window_builder.with_window_icon(Some(icon));
+        self.icon_path = Some(path.into());
+        self
+    }
+
+    /// The event loop resolves the window handle of the parent right before building
+    /// # Safety
+    /// `parent_window_handle` must be a valid handle of the parent window
+    pub(crate) unsafe fn with_parent_window_handle(
+        mut self,
+        parent_window_handle: RawWindowHandle,
+    ) -> Self {
+        self.parent_window_handle = Some(parent_window_handle);
+        self
+    }
+
+    pub fn window_builder(&self) -> &WindowBuilder {
+        &self.window_builder
+    }
+
+    /// Whether the window accepts input from input methods once created
+    pub fn ime_allowed(&self) -> bool {
+        self.ime_allowed
+    }
+
+    pub fn x11_parent_window(&self) -> Option<u64> {
+        self.x11_parent_window
+    }
+
+    pub fn is_centered(&self) -> bool {
+        self.centered
+    }
+
+    pub fn parent(&self) -> Option<(WindowId, WinitParentRelation)> {
+        self.parent
+    }
+
+    pub fn x11_name(&self) -> Option<(&str, &str)> {
+        self.x11_name
+            .as_ref()
+            .map(|(general, instance)| (general.as_str(), instance.as_str()))
+    }
+
+    pub fn x11_window_type(&self) -> Option<WinitX11WindowType> {
+        self.x11_window_type
+    }
+
+    pub fn x11_override_redirect(&self) -> Option<bool> {
+        self.x11_override_redirect
+    }
+
+    pub fn x11_base_size(&self) -> Option<PhysicalSize<u32>> {
+        self.x11_base_size
+    }
+
+    pub fn wayland_name(&self) -> Option<(&str, &str)> {
+        self.wayland_name
+            .as_ref()
+            .map(|(general, instance)| (general.as_str(), instance.as_str()))
+    }
+
+    pub fn macos_full_size(&self) -> Option<bool> {
+        self.macos_full_size
+    }
+
+    /// The file of the window icon, if it was loaded from one
+    pub fn icon_path(&self) -> Option<&Path> {
+        self.icon_path.as_deref()
+    }
+
+    /// Build the window and apply the library options
+    pub fn build<T: 'static>(self, event_loop: &EventLoopWindowTarget<T>) -> Result<Window> {
+        let is_visible = self.window_builder.window_attributes().visible;
+        let parent = match self.parent {
+            Some((parent_id, relation)) => Some((
+                relation,
+                self.parent_window_handle
+                    .ok_or(WinitError::WindowNotFound(parent_id))?,
+            )),
+            None => None,
+        };
+
+        let mut window_builder = self.window_builder;
+        if let Some((relation, parent_window_handle)) = parent {
+            window_builder = attach_to_parent(window_builder, relation, parent_window_handle);
+        }
+        // winit keeps a single name for both backends, so only the one of the backend in use is set
+        #[cfg(x11_platform)]
+        if let Some((general, instance)) = self.x11_name.filter(|_| event_loop.is_x11()) {
+            window_builder = WindowBuilderExtX11::with_name(window_builder, general, instance);
+        }
+        #[cfg(x11_platform)]
+        {
+            if let Some(window_type) = self.x11_window_type {
+                window_builder = window_builder.with_x11_window_type(vec![window_type.into()]);
+            }
+            if let Some(override_redirect) = self.x11_override_redirect {
+                window_builder = window_builder.with_override_redirect(override_redirect);
+            }
+            if let Some(base_size) = self.x11_base_size {
+                window_builder = window_builder.with_base_size(base_size);
+            }
+        }
+        #[cfg(wayland_platform)]
+        if let Some((general, instance)) = self.wayland_name.filter(|_| event_loop.is_wayland()) {
+            window_builder = WindowBuilderExtWayland::with_name(window_builder, general, instance);
+        }
+        #[cfg(target_os = "macos")]
+        if let Some(full_size) = self.macos_full_size {
+            window_builder = window_builder
+                .with_titlebar_transparent(full_size)
+                .with_fullsize_content_view(full_size)
+                .with_title_hidden(full_size);
+        }
+        // the X11 transient hint and the reparenting must be applied before the window is mapped
+        #[cfg(x11_platform)]
+        let needs_x11_setup = self.x11_parent_window.is_some()
+            || matches!(
+                parent,
+                Some((WinitParentRelation::Owned, RawWindowHandle::Xlib(_)))
+            );
+        #[cfg(not(x11_platform))]
+        let needs_x11_setup = false;
+        // the window is shown once it is centered or set up to not let it jump
+        let is_shown_later = self.centered || needs_x11_setup;
+        let window = match is_shown_later {
+            true => window_builder.with_visible(false),
+            false => window_builder,
+        }
+        .build(event_loop)?;
+        window.set_ime_allowed(self.ime_allowed);
+
+        #[cfg(x11_platform)]
+        if let Some((WinitParentRelation::Owned, RawWindowHandle::Xlib(owner))) = parent {
+            crate::x11::set_transient_for(&window, owner.window)?;
+        }
+
+        #[cfg(x11_platform)]
+        if let Some(parent_window) = self.x11_parent_window {
+            crate::x11::embed_into(&window, parent_window as std::ffi::c_ulong)?;
+        }
+
+        if self.centered {
+            if let Some(monitor) = event_loop.primary_monitor() {
+                center_on_monitor(&window, &monitor);
+            }
+        }
+        if is_shown_later {
+            window.set_visible(is_visible);
+        }
+        Ok(window)
+    }
+}
+
+/// Platforms without owned windows create a regular top-level window instead
+fn attach_to_parent(
+    window_builder: WindowBuilder,
+    relation: WinitParentRelation,
+    parent_window_handle: RawWindowHandle,
+) -> WindowBuilder {
+    match (relation, parent_window_handle) {
+        (WinitParentRelation::Child, _) => unsafe {
+            window_builder.with_parent_window(Some(parent_window_handle))
+        },
+        #[cfg(target_os = "windows")]
+        (WinitParentRelation::Owned, RawWindowHandle::Win32(owner)) => {
+            window_builder.with_owner_window(owner.hwnd as isize)
+        }
+        // child windows on macOS are top-level windows that follow their parent
+        #[cfg(target_os = "macos")]
+        (WinitParentRelation::Owned, RawWindowHandle::AppKit(_)) => unsafe {
+            window_builder.with_parent_window(Some(parent_window_handle))
+        },
+        // X11 owners are set once the window exists
+        (WinitParentRelation::Owned, _) => window_builder,
+    }
+}
+
+/// The size of the decorations is only known once the window is created
+fn center_on_monitor(window: &Window, monitor: &MonitorHandle) {
+    let monitor_position = monitor.position();
+    let monitor_size = monitor.size();
+    let window_size = window.outer_size();
+
+    let offset = |monitor_extent: u32, window_extent: u32| {
+        (monitor_extent.saturating_sub(window_extent) / 2) as i32
+    };
+    window.set_outer_position(PhysicalPosition::new(
+        monitor_position.x + offset(monitor_size.width, window_size.width),
+        monitor_position.y + offset(monitor_size.height, window_size.height),
+    ));
+}
+
+impl Default for WinitWindowBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn update_window_builder(
+    window_builder: *mut ValueBox<WinitWindowBuilder>,
+    update: impl FnOnce(WindowBuilder) -> WindowBuilder,
+) -> value_box::Result<()> {
+    window_builder.replace_value(|window_builder| window_builder.map(update))
+}
+
 #[no_mangle]
-pub extern "C" fn winit_window_builder_new() -> *mut ValueBox<WindowBuilder> {
-    ValueBox::new(WindowBuilder::new()).into_raw()
+pub extern "C" fn winit_window_builder_new() -> *mut ValueBox<WinitWindowBuilder> {
+    ValueBox::new(WinitWindowBuilder::new()).into_raw()
 }
 
 #[no_mangle]
-pub extern "C" fn winit_window_builder_drop(window_builder: *mut ValueBox<WindowBuilder>) {
+pub extern "C" fn winit_window_builder_drop(window_builder: *mut ValueBox<WinitWindowBuilder>) {
     window_builder.release();
 }
 
 #[no_mangle]
 pub extern "C" fn winit_window_builder_with_title(
-    window_builder: *mut ValueBox<WindowBuilder>,
+    window_builder: *mut ValueBox<WinitWindowBuilder>,
     window_title: *mut ValueBox<StringBox>,
 ) {
     window_title
         .with_ref_ok(|window_title| {
-            window_builder
-                .replace_value(|window_builder| window_builder.with_title(window_title.to_string()))
+            update_window_builder(window_builder, |window_builder| {
+                window_builder.with_title(window_title.to_string())
+            })
         })
         .log();
 }
 
 #[no_mangle]
 pub extern "C" fn winit_window_builder_with_decorations(
-    window_builder: *mut ValueBox<WindowBuilder>,
+    window_builder: *mut ValueBox<WinitWindowBuilder>,
     with_decorations: bool,
 ) {
-    window_builder
-        .replace_value(|window_builder| window_builder.with_decorations(with_decorations))
-        .log();
+    update_window_builder(window_builder, |window_builder| {
+        window_builder.with_decorations(with_decorations)
+    })
+    .log();
 }
 
 #[no_mangle]
 pub extern "C" fn winit_window_builder_with_transparency(
-    window_builder: *mut ValueBox<WindowBuilder>,
+    window_builder: *mut ValueBox<WinitWindowBuilder>,
     with_transparency: bool,
 ) {
-    window_builder
-        .replace_value(|window_builder| window_builder.with_transparent(with_transparency))
-        .log();
+    update_window_builder(window_builder, |window_builder| {
+        window_builder.with_transparent(with_transparency)
+    })
+    .log();
 }
 
 #[no_mangle]
 pub extern "C" fn winit_window_builder_with_resizable(
-    window_builder: *mut ValueBox<WindowBuilder>,
+    window_builder: *mut ValueBox<WinitWindowBuilder>,
     with_resizable: bool,
 ) {
-    window_builder
-        .replace_value(|window_builder| window_builder.with_resizable(with_resizable))
-        .log();
+    update_window_builder(window_builder, |window_builder| {
+        window_builder.with_resizable(with_resizable)
+    })
+    .log();
 }
 
 #[no_mangle]
 pub extern "C" fn winit_window_builder_with_dimensions(
-    window_builder: *mut ValueBox<WindowBuilder>,
+    window_builder: *mut ValueBox<WinitWindowBuilder>,
     width: f64,
     height: f64,
+) {
+    update_window_builder(window_builder, |window_builder| {
+        window_builder.with_inner_size(LogicalSize::new(width, height))
+    })
+    .log();
+}
+
+#[no_mangle]
+pub extern "C" fn winit_window_builder_with_physical_dimensions(
+    window_builder: *mut ValueBox<WinitWindowBuilder>,
+    width: u32,
+    height: u32,
+) {
+    update_window_builder(window_builder, |window_builder| {
+        window_builder.with_inner_size(PhysicalSize::new(width, height))
+    })
+    .log();
+}
+
+/// Set the logical position of the top-left corner of the window including decorations
+#[no_mangle]
+pub extern "C" fn winit_window_builder_with_position(
+    window_builder: *mut ValueBox<WinitWindowBuilder>,
+    x: f64,
+    y: f64,
+) {
+    update_window_builder(window_builder, |window_builder| {
+        window_builder.with_position(LogicalPosition::new(x, y))
+    })
+    .log();
+}
+
+/// Set the physical position of the top-left corner of the window including decorations
+#[no_mangle]
+pub extern "C" fn winit_window_builder_with_physical_position(
+    window_builder: *mut ValueBox<WinitWindowBuilder>,
+    x: i32,
+    y: i32,
+) {
+    update_window_builder(window_builder, |window_builder| {
+        window_builder.with_position(PhysicalPosition::new(x, y))
+    })
+    .log();
+}
+
+/// Center the window on the primary monitor once it is created.
+/// Takes precedence over the position.
+#[no_mangle]
+pub extern "C" fn winit_window_builder_with_centered(
+    window_builder: *mut ValueBox<WinitWindowBuilder>,
+    centered: bool,
 ) {
     window_builder
-        .replace_value(
-            |window_builder| window_builder.with_inner_size(LogicalSize::new(width, height))
-        )
+        .replace_value(|window_builder| window_builder.with_centered(centered))
         .log();
 }
 
+/// Set the minimum logical size of the window.
+/// Once set the constraint can only be removed from the created window.
+#[no_mangle]
+pub extern "C" fn winit_window_builder_with_min_dimensions(
+    window_builder: *mut ValueBox<WinitWindowBuilder>,
+    width: f64,
+    height: f64,
+) {
+    update_window_builder(window_builder, |window_builder| {
+        window_builder.with_min_inner_size(LogicalSize::new(width, height))
+    })
+    .log();
+}
+
+/// Set the minimum physical size of the window.
+/// Once set the constraint can only be removed from the created window.
+#[no_mangle]
+pub extern "C" fn winit_window_builder_with_min_physical_dimensions(
+    window_builder: *mut ValueBox<WinitWindowBuilder>,
+    width: u32,
+    height: u32,
+) {
+    update_window_builder(window_builder, |window_builder| {
+        window_builder.with_min_inner_size(PhysicalSize::new(width, height))
+    })
+    .log();
+}
+
+/// Set the maximum logical size of the window.
+/// Once set the constraint can only be removed from the created window.
+#[no_mangle]
+pub extern "C" fn winit_window_builder_with_max_dimensions(
+    window_builder: *mut ValueBox<WinitWindowBuilder>,
+    width: f64,
+    height: f64,
+) {
+    update_window_builder(window_builder, |window_builder| {
+        window_builder.with_max_inner_size(LogicalSize::new(width, height))
+    })
+    .log();
+}
+
+/// Set the maximum physical size of the window.
+/// Once set the constraint can only be removed from the created window.
+#[no_mangle]
+pub extern "C" fn winit_window_builder_with_max_physical_dimensions(
+    window_builder: *mut ValueBox<WinitWindowBuilder>,
+    width: u32,
+    height: u32,
+) {
+    update_window_builder(window_builder, |window_builder| {
+        window_builder.with_max_inner_size(PhysicalSize::new(width, height))
+    })
+    .log();
+}
+
 #[no_mangle]
 pub extern "C" fn winit_window_builder_with_maximized(
-    window_builder: *mut ValueBox<WindowBuilder>,
+    window_builder: *mut ValueBox<WinitWindowBuilder>,
     with_maximized: bool,
+) {
+    update_window_builder(window_builder, |window_builder| {
+        window_builder.with_maximized(with_maximized)
+    })
+    .log();
+}
+
+/// Create the window in borderless fullscreen on a given monitor.
+/// When the monitor is null the current monitor is used.
+#[no_mangle]
+pub extern "C" fn winit_window_builder_with_borderless_fullscreen(
+    window_builder: *mut ValueBox<WinitWindowBuilder>,
+    monitor: *mut ValueBox<MonitorHandle>,
+) {
+    borderless_fullscreen(monitor)
+        .and_then(|fullscreen| {
+            update_window_builder(window_builder, |window_builder| {
+                window_builder.with_fullscreen(Some(fullscreen))
+            })
+        })
+        .log();
+}
+
+/// Create the window in exclusive fullscreen with a given video mode
+#[no_mangle]
+pub extern "C" fn winit_window_builder_with_exclusive_fullscreen(
+    window_builder: *mut ValueBox<WinitWindowBuilder>,
+    video_mode: *mut ValueBox<VideoMode>,
+) {
+    exclusive_fullscreen(video_mode)
+        .and_then(|fullscreen| {
+            update_window_builder(window_builder, |window_builder| {
+                window_builder.with_fullscreen(Some(fullscreen))
+            })
+        })
+        .log();
+}
+
+/// Set the window icon from RGBA8 pixels. Return false if the icon is not valid.
+/// # Safety
+/// `rgba` must point to at least `length` bytes
+#[no_mangle]
+pub unsafe extern "C" fn winit_window_builder_with_icon_rgba(
+    window_builder: *mut ValueBox<WinitWindowBuilder>,
+    width: u32,
+    height: u32,
+    rgba: *const u8,
+    length: usize,
+) -> bool {
+    icon_from_rgba(width, height, rgba, length)
+        .map_err(|err| err.boxed().into())
+        .and_then(|icon| {
+            window_builder.replace_value(|window_builder| window_builder.with_window_icon(icon))
+        })
+        .map(|_| true)
+        .or_log(false)
+}
+
+/// Decide whether the window accepts input from input methods once created. Enabled by default.
+#[no_mangle]
+pub extern "C" fn winit_window_builder_with_ime_allowed(
+    window_builder: *mut ValueBox<WinitWindowBuilder>,
+    ime_allowed: bool,
 ) {
     window_builder
-        .replace_value(|window_builder| window_builder.with_maximized(with_maximized))
+        .replace_value(|window_builder| window_builder.with_ime_allowed(ime_allowed))
         .log();
 }
 
 #[no_mangle]
 pub extern "C" fn winit_window_builder_with_visibility(
-    window_builder: *mut ValueBox<WindowBuilder>,
+    window_builder: *mut ValueBox<WinitWindowBuilder>,
     with_visibility: bool,
 ) {
-    window_builder
-        .replace_value(|window_builder| window_builder.with_visible(with_visibility))
-        .log();
+    update_window_builder(window_builder, |window_builder| {
+        window_builder.with_visible(with_visibility)
+    })
+    .log();
 }
 
 #[no_mangle]
 pub extern "C" fn winit_window_builder_with_always_on_top(
-    window_builder: *mut ValueBox<WindowBuilder>,
+    window_builder: *mut ValueBox<WinitWindowBuilder>,
     with_always_on_top: bool,
+) {
+    update_window_builder(window_builder, |window_builder| {
+        let level = match with_always_on_top {
+            true => WindowLevel::AlwaysOnTop,
+            false => WindowLevel::Normal,
+        };
+        window_builder.with_window_level(level)
+    })
+    .log();
+}
+
+/// Extend the content under a transparent and hidden title bar. Ignored on other platforms
+#[no_mangle]
+pub extern "C" fn winit_window_builder_with_full_size(
+    window_builder: *mut ValueBox<WinitWindowBuilder>,
+    with_full_size: bool,
 ) {
     window_builder
-        .replace_value(|window_builder| {
-            let level = match with_always_on_top {
-                true => WindowLevel::AlwaysOnTop,
-                false => WindowLevel::Normal,
-            };
-            window_builder.with_window_level(level)
+        .replace_value(|window_builder| window_builder.with_macos_full_size(with_full_size))
+        .log();
+}
+
+/// Set the `WM_CLASS` of the window, which desktop environments use to group windows.
+/// Only applied when the window is created on X11
+#[no_mangle]
+pub extern "C" fn winit_window_builder_with_x11_name(
+    window_builder: *mut ValueBox<WinitWindowBuilder>,
+    general: *mut ValueBox<StringBox>,
+    instance: *mut ValueBox<StringBox>,
+) {
+    general
+        .with_ref(|general| {
+            instance.with_ref(|instance| {
+                window_builder.replace_value(|window_builder| {
+                    window_builder.with_x11_name(general.to_string(), instance.to_string())
+                })
+            })
         })
         .log();
 }
 
-#[cfg(not(target_os = "macos"))]
+/// Set the `_NET_WM_WINDOW_TYPE` of the window. Ignored on other platforms
 #[no_mangle]
-pub extern "C" fn winit_window_builder_with_full_size(
-    _ptr_window_builder: *mut ValueBox<WindowBuilder>,
-    _with_full_size: bool,
+pub extern "C" fn winit_window_builder_with_x11_window_type(
+    window_builder: *mut ValueBox<WinitWindowBuilder>,
+    window_type: WinitX11WindowType,
 ) {
+    window_builder
+        .replace_value(|window_builder| window_builder.with_x11_window_type(window_type))
+        .log();
 }
 
-#[cfg(target_os = "macos")]
+/// Let the window bypass the window manager, for example for popups and tooltips
 #[no_mangle]
-pub extern "C" fn winit_window_builder_with_full_size(
-    window_builder: *mut ValueBox<WindowBuilder>,
-    with_full_size: bool,
+pub extern "C" fn winit_window_builder_with_x11_override_redirect(
+    window_builder: *mut ValueBox<WinitWindowBuilder>,
+    override_redirect: bool,
 ) {
     window_builder
         .replace_value(|window_builder| {
+            window_builder.with_x11_override_redirect(override_redirect)
+        })
+        .log();
+}
+
+/// Set the physical size the resize increments are counted from
+#[no_mangle]
+pub extern "C" fn winit_window_builder_with_x11_base_size(
+    window_builder: *mut ValueBox<WinitWindowBuilder>,
+    width: u32,
+    height: u32,
+) {
+    window_builder
+        .replace_value(|window_builder| {
+            window_builder.with_x11_base_size(PhysicalSize::new(width, height))
+        })
+        .log();
+}
+
+/// Embed the window into a window of another application given by its XID.
+/// Ignored on other platforms
+#[no_mangle]
+pub extern "C" fn winit_window_builder_with_x11_parent_window(
+    window_builder: *mut ValueBox<WinitWindowBuilder>,
+    parent_window: u64,
+) {
+    window_builder
+        .replace_value(|window_builder| window_builder.with_x11_parent_window(parent_window))
+        .log();
+}
+
+/// Create the window as a child or an owned window of another window of the polling event loop.
+/// The parent window must stay open as long as the window exists.
+/// Child windows are not supported on Wayland, owned windows are regular windows there.
+#[no_mangle]
+pub extern "C" fn winit_window_builder_with_parent_window(
+    window_builder: *mut ValueBox<WinitWindowBuilder>,
+    parent_window: *mut ValueBox<WindowRef>,
+    relation: WinitParentRelation,
+) {
+    parent_window
+        .with_ref_ok(|parent_window| parent_window.id())
+        .and_then(|parent_id| {
             window_builder
-                .with_titlebar_transparent(with_full_size)
-                .with_fullsize_content_view(with_full_size)
-                .with_title_hidden(with_full_size)
+                .replace_value(|window_builder| window_builder.with_parent(parent_id, relation))
+        })
+        .log();
+}
+
+/// Set the `app_id` of the window, which desktop environments use to group windows.
+/// Only applied when the window is created on Wayland
+#[no_mangle]
+pub extern "C" fn winit_window_builder_with_wayland_name(
+    window_builder: *mut ValueBox<WinitWindowBuilder>,
+    general: *mut ValueBox<StringBox>,
+    instance: *mut ValueBox<StringBox>,
+) {
+    general
+        .with_ref(|general| {
+            instance.with_ref(|instance| {
+                window_builder.replace_value(|window_builder| {
+                    window_builder.with_wayland_name(general.to_string(), instance.to_string())
+                })
+            })
         })
         .log();
 }
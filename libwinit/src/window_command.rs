@@ -0,0 +1,231 @@
+use winit::dpi::{PhysicalPosition, PhysicalSize, Position, Size};
+use winit::window::{
+    CursorGrabMode, CursorIcon, Fullscreen, Icon, ResizeDirection, UserAttentionType, Window,
+    WindowButtons, WindowLevel,
+};
+
+use crate::{CustomCursor, Result};
+
+/// A change to a window that must be applied on the event loop thread
+#[derive(Debug, Clone)]
+pub enum WindowCommand {
+    SetTitle(String),
+    /// `None` removes the icon
+    SetWindowIcon(Option<Icon>),
+    SetInnerSize(PhysicalSize<u32>),
+    /// `None` removes the constraint
+    SetMinInnerSize(Option<Size>),
+    /// `None` removes the constraint
+    SetMaxInnerSize(Option<Size>),
+    /// `None` removes the constraint
+    SetResizeIncrements(Option<Size>),
+    /// Width divided by height enforced by the library, `None` removes the constraint
+    SetAspectRatio(Option<f64>),
+    SetOuterPosition(PhysicalPosition<i32>),
+    SetCursorIcon(CursorIcon),
+    SetCustomCursor(CustomCursor),
+    /// Only applied while the window is focused, otherwise it is applied when the window
+    /// gains focus
+    SetCursorGrab(CursorGrabMode),
+    SetCursorVisible(bool),
+    /// Move the cursor to a position relative to the window
+    SetCursorPosition(Position),
+    SetMaximized(bool),
+    SetMinimized(bool),
+    SetVisible(bool),
+    /// `None` exits fullscreen
+    SetFullscreen(Option<Fullscreen>),
+    SetDecorations(bool),
+    SetResizable(bool),
+    /// Only supported on macOS and Wayland
+    SetTransparent(bool),
+    SetWindowLevel(WindowLevel),
+    SetEnabledButtons(WindowButtons),
+    SetImeAllowed(bool),
+    /// The area of the text caret relative to the window. winit 0.28 only accepts a position,
+    /// so the input method shows its candidate window below the area
+    SetImeCursorArea(Position, Size),
+    /// `None` cancels the request
+    RequestUserAttention(Option<UserAttentionType>),
+    /// Start moving the window with the mouse. Only works while a mouse button is pressed
+    DragWindow,
+    /// Start resizing the window with the mouse. Only works while a mouse button is pressed
+    DragResizeWindow(ResizeDirection),
+    /// When `false` the mouse input passes through the window
+    SetCursorHittest(bool),
+    /// Rectangles of the window that receive mouse input, `None` restores the whole window.
+    /// Only supported on X11
+    SetInputShape(Option<Vec<(PhysicalPosition<i32>, PhysicalSize<u32>)>>),
+    Focus,
+    RequestRedraw,
+    /// Remove the window from the event loop and close it
+    Destroy,
+}
+
+impl WindowCommand {
+    /// Must be called from the event loop thread
+    pub fn apply(self, window: &Window) -> Result<()> {
+        match self {
+            Self::SetTitle(title) => window.set_title(title.as_str()),
+            Self::SetWindowIcon(icon) => window.set_window_icon(icon),
+            Self::SetInnerSize(size) => window.set_inner_size(size),
+            Self::SetMinInnerSize(size) => window.set_min_inner_size(size),
+            Self::SetMaxInnerSize(size) => window.set_max_inner_size(size),
+            Self::SetResizeIncrements(increments) => window.set_resize_increments(increments),
+            Self::SetAspectRatio(aspect_ratio) => {
+                if let Some(aspect_ratio) = aspect_ratio {
+                    let size = window.inner_size();
+                    window.set_inner_size(constrain_aspect_ratio(size, size, aspect_ratio))
+                }
+            }
+            Self::SetOuterPosition(position) => window.set_outer_position(position),
+            Self::SetCursorIcon(cursor_icon) => window.set_cursor_icon(cursor_icon),
+            Self::SetCustomCursor(cursor) => cursor.apply(window)?,
+            Self::SetCursorGrab(mode) => {
+                if mode == CursorGrabMode::None || window.has_focus() {
+                    window.set_cursor_grab(mode)?
+                }
+            }
+            Self::SetCursorVisible(visible) => window.set_cursor_visible(visible),
+            Self::SetCursorPosition(position) => window.set_cursor_position(position)?,
+            Self::SetMaximized(maximized) => window.set_maximized(maximized),
+            Self::SetMinimized(minimized) => window.set_minimized(minimized),
+            Self::SetVisible(visible) => window.set_visible(visible),
+            Self::SetFullscreen(fullscreen) => window.set_fullscreen(fullscreen),
+            Self::SetDecorations(decorations) => window.set_decorations(decorations),
+            Self::SetResizable(resizable) => window.set_resizable(resizable),
+            Self::SetTransparent(transparent) => window.set_transparent(transparent),
+            Self::SetWindowLevel(level) => window.set_window_level(level),
+            Self::SetEnabledButtons(buttons) => window.set_enabled_buttons(buttons),
+            Self::SetImeAllowed(allowed) => window.set_ime_allowed(allowed),
+            Self::SetImeCursorArea(position, size) => {
+                let scale_factor = window.scale_factor();
+                let position = position.to_physical::<f64>(scale_factor);
+                let size = size.to_physical::<f64>(scale_factor);
+                window.set_ime_position(PhysicalPosition::new(position.x, position.y + size.height))
+            }
+            Self::RequestUserAttention(attention) => window.request_user_attention(attention),
+            Self::DragWindow => window.drag_window()?,
+            Self::DragResizeWindow(direction) => window.drag_resize_window(direction)?,
+            Self::SetCursorHittest(hittest) => window.set_cursor_hittest(hittest)?,
+            #[cfg(x11_platform)]
+            Self::SetInputShape(shape) => crate::x11::set_input_shape(window, shape.as_deref())?,
+            #[cfg(not(x11_platform))]
+            Self::SetInputShape(_) => return Err(crate::WinitError::NotX11Window),
+            Self::Focus => window.focus_window(),
+            Self::RequestRedraw => window.request_redraw(),
+            // the event loop drops the window instead
+            Self::Destroy => {}
+        }
+        Ok(())
+    }
+}
+
+/// Return the size closest to a given one that has the aspect ratio (width / height),
+/// keeping the dimension that changed the most since the previous size.
+/// A size that already has the aspect ratio is returned as is, so constraining
+/// a constrained size never triggers another resize
+pub(crate) fn constrain_aspect_ratio(
+    size: PhysicalSize<u32>,
+    previous_size: PhysicalSize<u32>,
+    aspect_ratio: f64,
+) -> PhysicalSize<u32> {
+    let height_for_width = (size.width as f64 / aspect_ratio).round().max(1.0) as u32;
+    let width_for_height = (size.height as f64 * aspect_ratio).round().max(1.0) as u32;
+    if size.height == height_for_width || size.width == width_for_height {
+        return size;
+    }
+
+    let width_change = size.width.abs_diff(previous_size.width);
+    let height_change = size.height.abs_diff(previous_size.height);
+
+    if width_change >= height_change {
+        PhysicalSize::new(size.width, height_for_width)
+    } else {
+        PhysicalSize::new(width_for_height, size.height)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn constrain(size: (u32, u32), previous_size: (u32, u32), aspect_ratio: f64) -> (u32, u32) {
+        let size = constrain_aspect_ratio(
+            PhysicalSize::new(size.0, size.1),
+            PhysicalSize::new(previous_size.0, previous_size.1),
+            aspect_ratio,
+        );
+        (size.width, size.height)
+    }
+
+    #[test]
+    fn width_dominant_drag_keeps_the_width() {
+        assert_eq!(constrain((800, 310), (600, 300), 2.0), (800, 400));
+    }
+
+    #[test]
+    fn height_dominant_drag_keeps_the_height() {
+        assert_eq!(constrain((610, 400), (600, 300), 2.0), (800, 400));
+    }
+
+    #[test]
+    fn sizes_are_rounded_at_extreme_ratios() {
+        assert_eq!(constrain((1499, 300), (1000, 300), 1000.0), (1499, 1));
+        assert_eq!(constrain((1501, 300), (1000, 300), 1000.0), (1501, 2));
+        assert_eq!(constrain((300, 1499), (300, 1000), 0.001), (1, 1499));
+        assert_eq!(constrain((300, 1501), (300, 1000), 0.001), (2, 1501));
+    }
+
+    #[test]
+    fn sizes_are_at_least_one_pixel() {
+        assert_eq!(constrain((100, 50), (90, 50), 10_000.0), (100, 1));
+        assert_eq!(constrain((50, 100), (50, 90), 0.0001), (1, 100));
+    }
+
+    #[test]
+    fn constraining_twice_does_not_resize_again() {
+        let aspect_ratios = [
+            0.001,
+            0.1,
+            0.3,
+            0.7,
+            1.0,
+            1.5,
+            16.0 / 9.0,
+            3.0,
+            10.0,
+            1000.0,
+        ];
+        let sizes = [
+            (1, 1),
+            (1, 14),
+            (14, 1),
+            (3, 10),
+            (640, 480),
+            (1920, 1080),
+            (333, 777),
+        ];
+
+        for aspect_ratio in aspect_ratios {
+            for size in sizes {
+                for previous_size in sizes {
+                    let constrained = constrain(size, previous_size, aspect_ratio);
+                    // the platform reports the constrained size with the previous one cached
+                    assert_eq!(
+                        constrain(constrained, size, aspect_ratio),
+                        constrained,
+                        "{:?} from {:?} at {}",
+                        size,
+                        previous_size,
+                        aspect_ratio
+                    );
+                    assert_eq!(
+                        constrain(constrained, constrained, aspect_ratio),
+                        constrained
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,1228 @@
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value};
+use string_box::StringBox;
+use value_box::{ReturnBoxerResult, ValueBox, ValueBoxPointer};
+use winit::dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize, Position, Size};
+use winit::window::{Fullscreen, Icon, Theme, WindowBuilder, WindowButtons, WindowId, WindowLevel};
+
+use crate::enums::{
+    WinitConfigFormat, WinitConfigProblemKind, WinitParentRelation, WinitX11WindowType,
+};
+use crate::{Result, WinitError, WinitWindowBuilder};
+
+const WINDOW_KEYS: &[&str] = &[
+    "title",
+    "size",
+    "physical_size",
+    "position",
+    "physical_position",
+    "min_size",
+    "physical_min_size",
+    "max_size",
+    "physical_max_size",
+    "resize_increments",
+    "physical_resize_increments",
+    "centered",
+    "fullscreen",
+    "decorations",
+    "transparent",
+    "resizable",
+    "maximized",
+    "visible",
+    "level",
+    "theme",
+    "buttons",
+    "content_protected",
+    "active",
+    "icon",
+    "ime_allowed",
+    "parent",
+    "x11",
+    "wayland",
+    "macos",
+];
+const BUTTON_KEYS: &[&str] = &["close", "minimize", "maximize"];
+const PARENT_KEYS: &[&str] = &["window", "relation"];
+const X11_KEYS: &[&str] = &[
+    "name",
+    "window_type",
+    "override_redirect",
+    "base_size",
+    "parent_window",
+];
+const WAYLAND_KEYS: &[&str] = &["name"];
+const MACOS_KEYS: &[&str] = &["full_size"];
+
+const WINDOW_LEVELS: &[(&str, WindowLevel)] = &[
+    ("always_on_bottom", WindowLevel::AlwaysOnBottom),
+    ("normal", WindowLevel::Normal),
+    ("always_on_top", WindowLevel::AlwaysOnTop),
+];
+
+const THEMES: &[(&str, Theme)] = &[("light", Theme::Light), ("dark", Theme::Dark)];
+
+const BUTTONS: &[(&str, WindowButtons)] = &[
+    ("close", WindowButtons::CLOSE),
+    ("minimize", WindowButtons::MINIMIZE),
+    ("maximize", WindowButtons::MAXIMIZE),
+];
+
+const PARENT_RELATIONS: &[(&str, WinitParentRelation)] = &[
+    ("child", WinitParentRelation::Child),
+    ("owned", WinitParentRelation::Owned),
+];
+
+const X11_WINDOW_TYPES: &[(&str, WinitX11WindowType)] = &[
+    ("normal", WinitX11WindowType::Normal),
+    ("desktop", WinitX11WindowType::Desktop),
+    ("dock", WinitX11WindowType::Dock),
+    ("toolbar", WinitX11WindowType::Toolbar),
+    ("menu", WinitX11WindowType::Menu),
+    ("utility", WinitX11WindowType::Utility),
+    ("splash", WinitX11WindowType::Splash),
+    ("dialog", WinitX11WindowType::Dialog),
+    ("dropdown_menu", WinitX11WindowType::DropdownMenu),
+    ("popup_menu", WinitX11WindowType::PopupMenu),
+    ("tooltip", WinitX11WindowType::Tooltip),
+    ("notification", WinitX11WindowType::Notification),
+    ("combo", WinitX11WindowType::Combo),
+    ("dnd", WinitX11WindowType::Dnd),
+];
+
+/// A window description that is read from and written to JSON or TOML.
+/// Sizes and positions are logical unless given with the `physical_` prefix.
+/// Options of other platforms are validated but ignored.
+#[derive(Debug, Clone, Default)]
+pub struct WindowConfig {
+    pub title: Option<String>,
+    pub size: Option<Size>,
+    pub position: Option<Position>,
+    pub min_size: Option<Size>,
+    pub max_size: Option<Size>,
+    pub resize_increments: Option<Size>,
+    pub centered: Option<bool>,
+    /// Borderless fullscreen on the current monitor. Builders in exclusive fullscreen
+    /// or on a given monitor are described as borderless fullscreen, because monitors
+    /// and video modes are only known to a running event loop.
+    pub fullscreen: Option<bool>,
+    pub decorations: Option<bool>,
+    pub transparent: Option<bool>,
+    pub resizable: Option<bool>,
+    pub maximized: Option<bool>,
+    pub visible: Option<bool>,
+    pub level: Option<WindowLevel>,
+    pub theme: Option<Theme>,
+    /// Buttons that are left out of the table stay enabled
+    pub enabled_buttons: Option<WindowButtons>,
+    pub content_protected: Option<bool>,
+    pub active: Option<bool>,
+    /// A PNG file, relative paths are resolved against the working directory
+    pub icon: Option<PathBuf>,
+    pub ime_allowed: Option<bool>,
+    /// A window of the same event loop, so only meaningful while the application runs
+    pub parent: Option<(WindowId, WinitParentRelation)>,
+    pub x11: X11Config,
+    pub wayland: WaylandConfig,
+    pub macos: MacOSConfig,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct X11Config {
+    /// The general and instance parts of `WM_CLASS`
+    pub name: Option<(String, String)>,
+    pub window_type: Option<WinitX11WindowType>,
+    pub override_redirect: Option<bool>,
+    pub base_size: Option<PhysicalSize<u32>>,
+    /// The XID of a window of another application to embed the window into
+    pub parent_window: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct WaylandConfig {
+    /// The general and instance parts of the `app_id`
+    pub name: Option<(String, String)>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MacOSConfig {
+    pub full_size: Option<bool>,
+}
+
+/// A problem with a single key of a window configuration
+#[derive(Debug, Clone)]
+pub struct ConfigProblem {
+    /// Dotted path of the key, empty for problems with the whole document
+    pub key: String,
+    pub kind: WinitConfigProblemKind,
+    pub message: String,
+}
+
+/// All problems found in a window configuration
+#[derive(Debug, Clone, Default)]
+pub struct WindowConfigError {
+    problems: Vec<ConfigProblem>,
+}
+
+impl WindowConfigError {
+    fn single(kind: WinitConfigProblemKind, key: &str, message: String) -> Self {
+        Self {
+            problems: vec![ConfigProblem {
+                key: key.to_string(),
+                kind,
+                message,
+            }],
+        }
+    }
+
+    pub fn problems(&self) -> &[ConfigProblem] {
+        self.problems.as_slice()
+    }
+}
+
+impl Display for ConfigProblem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.key.is_empty() {
+            true => write!(f, "{}", self.message),
+            false => write!(f, "{}: {}", self.key, self.message),
+        }
+    }
+}
+
+impl Display for WindowConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (index, problem) in self.problems.iter().enumerate() {
+            if index > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{}", problem)?;
+        }
+        Ok(())
+    }
+}
+
+impl WindowConfig {
+    pub fn parse(config: &str, format: WinitConfigFormat) -> Result<Self> {
+        let value = match format {
+            WinitConfigFormat::Json => {
+                serde_json::from_str::<Value>(config).map_err(|error| error.to_string())
+            }
+            WinitConfigFormat::Toml => {
+                toml::from_str::<Value>(config).map_err(|error| error.to_string())
+            }
+        }
+        .map_err(|message| {
+            WinitError::InvalidWindowConfig(WindowConfigError::single(
+                WinitConfigProblemKind::Syntax,
+                "",
+                message,
+            ))
+        })?;
+        Self::from_value(&value)
+    }
+
+    /// Read the configuration reporting all unknown keys and invalid values at once
+    pub fn from_value(value: &Value) -> Result<Self> {
+        let mut reader = ConfigReader::default();
+        let mut config = Self::default();
+
+        if let Some(window) = reader.table(value, "", WINDOW_KEYS) {
+            config.title = reader.read(&window, "title", string);
+            config.size = reader.read_either(
+                &window,
+                ("size", logical_size),
+                ("physical_size", physical_size),
+            );
+            config.position = reader.read_either(
+                &window,
+                ("position", logical_position),
+                ("physical_position", physical_position),
+            );
+            config.min_size = reader.read_either(
+                &window,
+                ("min_size", logical_size),
+                ("physical_min_size", physical_size),
+            );
+            config.max_size = reader.read_either(
+                &window,
+                ("max_size", logical_size),
+                ("physical_max_size", physical_size),
+            );
+            config.resize_increments = reader.read_either(
+                &window,
+                ("resize_increments", logical_size),
+                ("physical_resize_increments", physical_size),
+            );
+            config.centered = reader.read(&window, "centered", boolean);
+            config.fullscreen = reader.read(&window, "fullscreen", boolean);
+            config.decorations = reader.read(&window, "decorations", boolean);
+            config.transparent = reader.read(&window, "transparent", boolean);
+            config.resizable = reader.read(&window, "resizable", boolean);
+            config.maximized = reader.read(&window, "maximized", boolean);
+            config.visible = reader.read(&window, "visible", boolean);
+            config.level = reader.read(&window, "level", |value| named(value, WINDOW_LEVELS));
+            config.theme = reader.read(&window, "theme", |value| named(value, THEMES));
+            if let Some(buttons) = reader.section(&window, "buttons", BUTTON_KEYS) {
+                let mut enabled_buttons = WindowButtons::all();
+                for (key, button) in BUTTONS {
+                    if reader.read(&buttons, key, boolean) == Some(false) {
+                        enabled_buttons.remove(*button);
+                    }
+                }
+                config.enabled_buttons = Some(enabled_buttons);
+            }
+            config.content_protected = reader.read(&window, "content_protected", boolean);
+            config.active = reader.read(&window, "active", boolean);
+            config.icon = reader.read(&window, "icon", |value| string(value).map(PathBuf::from));
+            config.ime_allowed = reader.read(&window, "ime_allowed", boolean);
+            if let Some(parent) = reader.section(&window, "parent", PARENT_KEYS) {
+                let parent_window = reader.read(&parent, "window", unsigned_64);
+                let relation = reader
+                    .read(&parent, "relation", |value| named(value, PARENT_RELATIONS))
+                    .unwrap_or(WinitParentRelation::Child);
+                config.parent =
+                    parent_window.map(|parent_window| (WindowId::from(parent_window), relation));
+            }
+
+            if let Some(x11) = reader.section(&window, "x11", X11_KEYS) {
+                config.x11 = X11Config {
+                    name: reader.read(&x11, "name", name),
+                    window_type: reader
+                        .read(&x11, "window_type", |value| named(value, X11_WINDOW_TYPES)),
+                    override_redirect: reader.read(&x11, "override_redirect", boolean),
+                    base_size: reader.read(&x11, "base_size", |value| {
+                        pair(value, "width", "height", unsigned)
+                            .map(|(width, height)| PhysicalSize::new(width, height))
+                    }),
+                    parent_window: reader.read(&x11, "parent_window", unsigned_64),
+                };
+            }
+            if let Some(wayland) = reader.section(&window, "wayland", WAYLAND_KEYS) {
+                config.wayland = WaylandConfig {
+                    name: reader.read(&wayland, "name", name),
+                };
+            }
+            if let Some(macos) = reader.section(&window, "macos", MACOS_KEYS) {
+                config.macos = MacOSConfig {
+                    full_size: reader.read(&macos, "full_size", boolean),
+                };
+            }
+        }
+
+        match reader.problems.is_empty() {
+            true => Ok(config),
+            false => Err(WinitError::InvalidWindowConfig(WindowConfigError {
+                problems: reader.problems,
+            })),
+        }
+    }
+
+    /// Describe the settings of a builder. Icons that were not loaded from a file
+    /// can't be described and are left out.
+    pub fn from_builder(window_builder: &WinitWindowBuilder) -> Self {
+        let attributes = window_builder.window_builder().window_attributes();
+        Self {
+            title: Some(attributes.title.clone()),
+            size: attributes.inner_size,
+            position: attributes.position,
+            min_size: attributes.min_inner_size,
+            max_size: attributes.max_inner_size,
+            resize_increments: attributes.resize_increments,
+            centered: Some(window_builder.is_centered()),
+            fullscreen: Some(attributes.fullscreen.is_some()),
+            decorations: Some(attributes.decorations),
+            transparent: Some(attributes.transparent),
+            resizable: Some(attributes.resizable),
+            maximized: Some(attributes.maximized),
+            visible: Some(attributes.visible),
+            level: Some(attributes.window_level),
+            theme: attributes.preferred_theme,
+            enabled_buttons: Some(attributes.enabled_buttons),
+            content_protected: Some(attributes.content_protected),
+            active: Some(attributes.active),
+            icon: window_builder.icon_path().map(Path::to_path_buf),
+            ime_allowed: Some(window_builder.ime_allowed()),
+            parent: window_builder.parent(),
+            x11: X11Config {
+                name: window_builder.x11_name().map(owned_name),
+                window_type: window_builder.x11_window_type(),
+                override_redirect: window_builder.x11_override_redirect(),
+                base_size: window_builder.x11_base_size(),
+                parent_window: window_builder.x11_parent_window(),
+            },
+            wayland: WaylandConfig {
+                name: window_builder.wayland_name().map(owned_name),
+            },
+            macos: MacOSConfig {
+                full_size: window_builder.macos_full_size(),
+            },
+        }
+    }
+
+    pub fn to_value(&self) -> Value {
+        let mut window = Map::new();
+        insert(&mut window, "title", self.title.as_deref());
+        insert_size(&mut window, "size", self.size);
+        match self.position {
+            Some(Position::Logical(position)) => insert(
+                &mut window,
+                "position",
+                Some(pair_value("x", position.x, "y", position.y)),
+            ),
+            Some(Position::Physical(position)) => insert(
+                &mut window,
+                "physical_position",
+                Some(pair_value("x", position.x, "y", position.y)),
+            ),
+            None => {}
+        }
+        insert_size(&mut window, "min_size", self.min_size);
+        insert_size(&mut window, "max_size", self.max_size);
+        insert_size(&mut window, "resize_increments", self.resize_increments);
+        insert(&mut window, "centered", self.centered);
+        insert(&mut window, "fullscreen", self.fullscreen);
+        insert(&mut window, "decorations", self.decorations);
+        insert(&mut window, "transparent", self.transparent);
+        insert(&mut window, "resizable", self.resizable);
+        insert(&mut window, "maximized", self.maximized);
+        insert(&mut window, "visible", self.visible);
+        insert(
+            &mut window,
+            "level",
+            self.level.map(|level| name_of(level, WINDOW_LEVELS)),
+        );
+        insert(
+            &mut window,
+            "theme",
+            self.theme.map(|theme| name_of(theme, THEMES)),
+        );
+        if let Some(enabled_buttons) = self.enabled_buttons {
+            let mut buttons = Map::new();
+            for (key, button) in BUTTONS {
+                insert(&mut buttons, key, Some(enabled_buttons.contains(*button)));
+            }
+            insert_section(&mut window, "buttons", buttons);
+        }
+        insert(&mut window, "content_protected", self.content_protected);
+        insert(&mut window, "active", self.active);
+        insert(
+            &mut window,
+            "icon",
+            self.icon.as_ref().map(|icon| icon.to_string_lossy()),
+        );
+        insert(&mut window, "ime_allowed", self.ime_allowed);
+        insert(
+            &mut window,
+            "parent",
+            self.parent.map(|(parent_window, relation)| {
+                pair_value(
+                    "window",
+                    u64::from(parent_window),
+                    "relation",
+                    name_of(relation, PARENT_RELATIONS),
+                )
+            }),
+        );
+
+        let mut x11 = Map::new();
+        insert(&mut x11, "name", self.x11.name.as_ref().map(name_value));
+        insert(
+            &mut x11,
+            "window_type",
+            self.x11
+                .window_type
+                .map(|window_type| name_of(window_type, X11_WINDOW_TYPES)),
+        );
+        insert(&mut x11, "override_redirect", self.x11.override_redirect);
+        insert(
+            &mut x11,
+            "base_size",
+            self.x11
+                .base_size
+                .map(|size| pair_value("width", size.width, "height", size.height)),
+        );
+        insert(&mut x11, "parent_window", self.x11.parent_window);
+        insert_section(&mut window, "x11", x11);
+
+        let mut wayland = Map::new();
+        insert(
+            &mut wayland,
+            "name",
+            self.wayland.name.as_ref().map(name_value),
+        );
+        insert_section(&mut window, "wayland", wayland);
+
+        let mut macos = Map::new();
+        insert(&mut macos, "full_size", self.macos.full_size);
+        insert_section(&mut window, "macos", macos);
+
+        Value::Object(window)
+    }
+
+    pub fn serialize(&self, format: WinitConfigFormat) -> Result<String> {
+        let value = self.to_value();
+        match format {
+            WinitConfigFormat::Json => {
+                serde_json::to_string_pretty(&value).map_err(|error| error.to_string())
+            }
+            WinitConfigFormat::Toml => toml::to_string(&value).map_err(|error| error.to_string()),
+        }
+        .map_err(WinitError::WindowConfigSerialization)
+    }
+
+    /// Create a builder with the settings of the configuration.
+    /// The icon is loaded here and reported as an invalid value if it can't be read.
+    pub fn into_builder(self) -> Result<WinitWindowBuilder> {
+        let icon = match &self.icon {
+            Some(path) => Some(load_icon(path).map_err(|message| {
+                WinitError::InvalidWindowConfig(WindowConfigError::single(
+                    WinitConfigProblemKind::InvalidValue,
+                    "icon",
+                    message,
+                ))
+            })?),
+            None => None,
+        };
+
+        let mut window_builder = WindowBuilder::new();
+        if let Some(title) = self.title {
+            window_builder = window_builder.with_title(title);
+        }
+        if let Some(size) = self.size {
+            window_builder = window_builder.with_inner_size(size);
+        }
+        if let Some(position) = self.position {
+            window_builder = window_builder.with_position(position);
+        }
+        if let Some(min_size) = self.min_size {
+            window_builder = window_builder.with_min_inner_size(min_size);
+        }
+        if let Some(max_size) = self.max_size {
+            window_builder = window_builder.with_max_inner_size(max_size);
+        }
+        if let Some(resize_increments) = self.resize_increments {
+            window_builder = window_builder.with_resize_increments(resize_increments);
+        }
+        if let Some(fullscreen) = self.fullscreen {
+            window_builder =
+                window_builder.with_fullscreen(fullscreen.then_some(Fullscreen::Borderless(None)));
+        }
+        if let Some(decorations) = self.decorations {
+            window_builder = window_builder.with_decorations(decorations);
+        }
+        if let Some(transparent) = self.transparent {
+            window_builder = window_builder.with_transparent(transparent);
+        }
+        if let Some(resizable) = self.resizable {
+            window_builder = window_builder.with_resizable(resizable);
+        }
+        if let Some(maximized) = self.maximized {
+            window_builder = window_builder.with_maximized(maximized);
+        }
+        if let Some(visible) = self.visible {
+            window_builder = window_builder.with_visible(visible);
+        }
+        if let Some(level) = self.level {
+            window_builder = window_builder.with_window_level(level);
+        }
+        if let Some(theme) = self.theme {
+            window_builder = window_builder.with_theme(Some(theme));
+        }
+        if let Some(enabled_buttons) = self.enabled_buttons {
+            window_builder = window_builder.with_enabled_buttons(enabled_buttons);
+        }
+        if let Some(content_protected) = self.content_protected {
+            window_builder = window_builder.with_content_protected(content_protected);
+        }
+        if let Some(active) = self.active {
+            window_builder = window_builder.with_active(active);
+        }
+
+        let mut builder = WinitWindowBuilder::new().map(|_| window_builder);
+        if let (Some(icon), Some(path)) = (icon, self.icon) {
+            builder = builder.with_window_icon_file(icon, path);
+        }
+        if let Some(centered) = self.centered {
+            builder = builder.with_centered(centered);
+        }
+        if let Some(ime_allowed) = self.ime_allowed {
+            builder = builder.with_ime_allowed(ime_allowed);
+        }
+        if let Some((parent_window, relation)) = self.parent {
+            builder = builder.with_parent(parent_window, relation);
+        }
+        if let Some((general, instance)) = self.x11.name {
+            builder = builder.with_x11_name(general, instance);
+        }
+        if let Some(window_type) = self.x11.window_type {
+            builder = builder.with_x11_window_type(window_type);
+        }
+        if let Some(override_redirect) = self.x11.override_redirect {
+            builder = builder.with_x11_override_redirect(override_redirect);
+        }
+        if let Some(base_size) = self.x11.base_size {
+            builder = builder.with_x11_base_size(base_size);
+        }
+        if let Some(parent_window) = self.x11.parent_window {
+            builder = builder.with_x11_parent_window(parent_window);
+        }
+        if let Some((general, instance)) = self.wayland.name {
+            builder = builder.with_wayland_name(general, instance);
+        }
+        if let Some(full_size) = self.macos.full_size {
+            builder = builder.with_macos_full_size(full_size);
+        }
+        Ok(builder)
+    }
+}
+
+fn owned_name((general, instance): (&str, &str)) -> (String, String) {
+    (general.to_string(), instance.to_string())
+}
+
+/// Value parsers describe the problem with a value as a message
+type ParseResult<T> = std::result::Result<T, String>;
+type Parse<T> = fn(&Value) -> ParseResult<T>;
+
+/// A table of the configuration together with its dotted path
+struct Table<'a> {
+    path: String,
+    entries: &'a Map<String, Value>,
+}
+
+impl Table<'_> {
+    fn key_path(&self, key: &str) -> String {
+        match self.path.is_empty() {
+            true => key.to_string(),
+            false => format!("{}.{}", self.path, key),
+        }
+    }
+}
+
+/// Collects the problems of all keys instead of stopping at the first one
+#[derive(Default)]
+struct ConfigReader {
+    problems: Vec<ConfigProblem>,
+}
+
+impl ConfigReader {
+    fn problem(&mut self, kind: WinitConfigProblemKind, key: String, message: String) {
+        self.problems.push(ConfigProblem { key, kind, message });
+    }
+
+    fn table<'a>(
+        &mut self,
+        value: &'a Value,
+        path: &str,
+        known_keys: &[&str],
+    ) -> Option<Table<'a>> {
+        let entries = match value.as_object() {
+            Some(entries) => entries,
+            None => {
+                self.problem(
+                    WinitConfigProblemKind::InvalidValue,
+                    path.to_string(),
+                    expected("a table", value),
+                );
+                return None;
+            }
+        };
+
+        let table = Table {
+            path: path.to_string(),
+            entries,
+        };
+        for key in entries.keys() {
+            if !known_keys.contains(&key.as_str()) {
+                self.problem(
+                    WinitConfigProblemKind::UnknownKey,
+                    table.key_path(key),
+                    "unknown key".to_string(),
+                );
+            }
+        }
+        Some(table)
+    }
+
+    fn section<'a>(
+        &mut self,
+        table: &Table<'a>,
+        key: &str,
+        known_keys: &[&str],
+    ) -> Option<Table<'a>> {
+        table
+            .entries
+            .get(key)
+            .and_then(|value| self.table(value, &table.key_path(key), known_keys))
+    }
+
+    fn read<T>(
+        &mut self,
+        table: &Table,
+        key: &str,
+        parse: impl FnOnce(&Value) -> ParseResult<T>,
+    ) -> Option<T> {
+        let value = table.entries.get(key)?;
+        parse(value)
+            .map_err(|message| {
+                self.problem(
+                    WinitConfigProblemKind::InvalidValue,
+                    table.key_path(key),
+                    message,
+                )
+            })
+            .ok()
+    }
+
+    /// Read a setting that can be given in either logical or physical units, but not both
+    fn read_either<T>(
+        &mut self,
+        table: &Table,
+        (logical_key, logical): (&str, Parse<T>),
+        (physical_key, physical): (&str, Parse<T>),
+    ) -> Option<T> {
+        let logical_value = self.read(table, logical_key, logical);
+        let physical_value = self.read(table, physical_key, physical);
+        if logical_value.is_some() && physical_value.is_some() {
+            self.problem(
+                WinitConfigProblemKind::InvalidValue,
+                table.key_path(physical_key),
+                format!("can't be combined with {}", logical_key),
+            );
+            return None;
+        }
+        logical_value.or(physical_value)
+    }
+}
+
+fn expected(what: &str, value: &Value) -> String {
+    format!("expected {}, got {}", what, value)
+}
+
+fn string(value: &Value) -> ParseResult<String> {
+    value
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| expected("a string", value))
+}
+
+fn boolean(value: &Value) -> ParseResult<bool> {
+    value.as_bool().ok_or_else(|| expected("a boolean", value))
+}
+
+fn number(value: &Value) -> ParseResult<f64> {
+    value
+        .as_f64()
+        .filter(|number| number.is_finite())
+        .ok_or_else(|| expected("a number", value))
+}
+
+fn non_negative(value: &Value) -> ParseResult<f64> {
+    number(value)
+        .ok()
+        .filter(|number| *number >= 0.0)
+        .ok_or_else(|| expected("a non-negative number", value))
+}
+
+fn unsigned(value: &Value) -> ParseResult<u32> {
+    value
+        .as_u64()
+        .and_then(|number| u32::try_from(number).ok())
+        .ok_or_else(|| expected("a non-negative integer", value))
+}
+
+fn unsigned_64(value: &Value) -> ParseResult<u64> {
+    value
+        .as_u64()
+        .ok_or_else(|| expected("a non-negative integer", value))
+}
+
+fn signed(value: &Value) -> ParseResult<i32> {
+    value
+        .as_i64()
+        .and_then(|number| i32::try_from(number).ok())
+        .ok_or_else(|| expected("an integer", value))
+}
+
+fn named<T: Copy>(value: &Value, names: &[(&str, T)]) -> ParseResult<T> {
+    let known_names = || {
+        names
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    value
+        .as_str()
+        .and_then(|value| names.iter().find(|(name, _)| *name == value))
+        .map(|(_, named)| *named)
+        .ok_or_else(|| expected(&format!("one of {}", known_names()), value))
+}
+
+fn name_of<T: Copy + PartialEq>(value: T, names: &[(&'static str, T)]) -> &'static str {
+    names
+        .iter()
+        .find(|(_, named)| *named == value)
+        .map(|(name, _)| *name)
+        .unwrap_or_default()
+}
+
+/// A table with exactly two given keys
+fn pair<T>(
+    value: &Value,
+    first: &str,
+    second: &str,
+    parse: impl Fn(&Value) -> ParseResult<T>,
+) -> ParseResult<(T, T)> {
+    let expected_pair = || expected(&format!("a table with {} and {}", first, second), value);
+    let table = value
+        .as_object()
+        .filter(|table| table.len() == 2)
+        .ok_or_else(expected_pair)?;
+    let component = |key: &str| table.get(key).ok_or_else(expected_pair).and_then(&parse);
+    Ok((component(first)?, component(second)?))
+}
+
+fn logical_size(value: &Value) -> ParseResult<Size> {
+    pair(value, "width", "height", non_negative)
+        .map(|(width, height)| LogicalSize::new(width, height).into())
+}
+
+fn physical_size(value: &Value) -> ParseResult<Size> {
+    pair(value, "width", "height", unsigned)
+        .map(|(width, height)| PhysicalSize::new(width, height).into())
+}
+
+fn logical_position(value: &Value) -> ParseResult<Position> {
+    pair(value, "x", "y", number).map(|(x, y)| LogicalPosition::new(x, y).into())
+}
+
+fn physical_position(value: &Value) -> ParseResult<Position> {
+    pair(value, "x", "y", signed).map(|(x, y)| PhysicalPosition::new(x, y).into())
+}
+
+fn name(value: &Value) -> ParseResult<(String, String)> {
+    pair(value, "general", "instance", string)
+}
+
+fn pair_value(
+    first: &str,
+    first_value: impl Into<Value>,
+    second: &str,
+    second_value: impl Into<Value>,
+) -> Value {
+    let mut table = Map::new();
+    table.insert(first.to_string(), first_value.into());
+    table.insert(second.to_string(), second_value.into());
+    Value::Object(table)
+}
+
+fn name_value((general, instance): &(String, String)) -> Value {
+    pair_value("general", general.as_str(), "instance", instance.as_str())
+}
+
+fn insert(table: &mut Map<String, Value>, key: &str, value: Option<impl Into<Value>>) {
+    if let Some(value) = value {
+        table.insert(key.to_string(), value.into());
+    }
+}
+
+fn insert_size(table: &mut Map<String, Value>, key: &str, size: Option<Size>) {
+    match size {
+        Some(Size::Logical(size)) => insert(
+            table,
+            key,
+            Some(pair_value("width", size.width, "height", size.height)),
+        ),
+        Some(Size::Physical(size)) => insert(
+            table,
+            &format!("physical_{}", key),
+            Some(pair_value("width", size.width, "height", size.height)),
+        ),
+        None => {}
+    }
+}
+
+/// Sections without settings are left out
+fn insert_section(table: &mut Map<String, Value>, key: &str, section: Map<String, Value>) {
+    if !section.is_empty() {
+        table.insert(key.to_string(), Value::Object(section));
+    }
+}
+
+fn load_icon(path: &Path) -> ParseResult<Icon> {
+    let file =
+        File::open(path).map_err(|error| format!("can't open {}: {}", path.display(), error))?;
+    let mut decoder = png::Decoder::new(file);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder
+        .read_info()
+        .map_err(|error| format!("can't decode {}: {}", path.display(), error))?;
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    let info = reader
+        .next_frame(&mut pixels)
+        .map_err(|error| format!("can't decode {}: {}", path.display(), error))?;
+    pixels.truncate(info.buffer_size());
+
+    let rgba = match info.color_type {
+        png::ColorType::Rgba => pixels,
+        png::ColorType::Rgb => pixels
+            .chunks_exact(3)
+            .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], u8::MAX])
+            .collect(),
+        png::ColorType::GrayscaleAlpha => pixels
+            .chunks_exact(2)
+            .flat_map(|gray| [gray[0], gray[0], gray[0], gray[1]])
+            .collect(),
+        png::ColorType::Grayscale => pixels
+            .iter()
+            .flat_map(|gray| [*gray, *gray, *gray, u8::MAX])
+            .collect(),
+        png::ColorType::Indexed => {
+            return Err(format!("can't expand the palette of {}", path.display()))
+        }
+    };
+    Icon::from_rgba(rgba, info.width, info.height).map_err(|error| error.to_string())
+}
+
+///////////////////////////////////////////////////////////////////////////////////////
+/////////////////////////// W I N D O W    C O N F I G ////////////////////////////////
+///////////////////////////////////////////////////////////////////////////////////////
+
+/// JSON configurations are objects, while a TOML document can't start with an inline table
+fn detect_format(config: &str) -> WinitConfigFormat {
+    match config.trim_start().starts_with('{') {
+        true => WinitConfigFormat::Json,
+        false => WinitConfigFormat::Toml,
+    }
+}
+
+fn builder_from_config(config: &str) -> Result<WinitWindowBuilder> {
+    WindowConfig::parse(config, detect_format(config)).and_then(WindowConfig::into_builder)
+}
+
+/// Create a window builder from a JSON or TOML window configuration, telling the format
+/// from the content. Return null if the configuration is not valid,
+/// use `winit_window_config_get_problems` to find out why.
+#[no_mangle]
+pub extern "C" fn winit_window_builder_from_config(
+    config: *mut ValueBox<StringBox>,
+) -> *mut ValueBox<WinitWindowBuilder> {
+    config
+        .with_ref(|config| builder_from_config(config.as_str()).map_err(|err| err.boxed().into()))
+        .map(|window_builder| ValueBox::new(window_builder).into_raw())
+        .or_log(std::ptr::null_mut())
+}
+
+/// Return the unknown keys and invalid values of a window configuration,
+/// none if a window builder can be created from it
+#[no_mangle]
+pub extern "C" fn winit_window_config_get_problems(
+    config: *mut ValueBox<StringBox>,
+) -> *mut ValueBox<WindowConfigError> {
+    config
+        .with_ref_ok(|config| match builder_from_config(config.as_str()) {
+            Err(WinitError::InvalidWindowConfig(config_error)) => config_error,
+            _ => WindowConfigError::default(),
+        })
+        .map(|config_error| ValueBox::new(config_error).into_raw())
+        .or_log(std::ptr::null_mut())
+}
+
+/// Write the settings of a window builder as JSON or TOML.
+/// Icons that were not loaded from a file are left out.
+#[no_mangle]
+pub extern "C" fn winit_window_builder_to_config(
+    window_builder: *mut ValueBox<WinitWindowBuilder>,
+    format: WinitConfigFormat,
+    config: *mut ValueBox<StringBox>,
+) -> bool {
+    window_builder
+        .with_ref(|window_builder| {
+            config.with_mut(|config| {
+                WindowConfig::from_builder(window_builder)
+                    .serialize(format)
+                    .map(|serialized| config.set_string(serialized))
+                    .map_err(|err| err.boxed().into())
+            })
+        })
+        .map(|_| true)
+        .or_log(false)
+}
+
+#[no_mangle]
+pub extern "C" fn winit_window_config_error_problem_count(
+    error: *mut ValueBox<WindowConfigError>,
+) -> usize {
+    error.with_ref_ok(|error| error.problems.len()).or_log(0)
+}
+
+fn with_problem<T: 'static>(
+    error: *mut ValueBox<WindowConfigError>,
+    index: usize,
+    callback: impl FnOnce(&ConfigProblem) -> T,
+) -> value_box::Result<T> {
+    error.with_ref(|error| {
+        error
+            .problems
+            .get(index)
+            .map(callback)
+            .ok_or_else(|| format!("There is no problem at index {}", index).into())
+    })
+}
+
+/// Return the kind of the problem at a given index (starting from 0)
+#[no_mangle]
+pub extern "C" fn winit_window_config_error_get_problem_kind(
+    error: *mut ValueBox<WindowConfigError>,
+    index: usize,
+) -> WinitConfigProblemKind {
+    with_problem(error, index, |problem| problem.kind).or_log(WinitConfigProblemKind::Syntax)
+}
+
+/// Write the dotted key of the problem at a given index (starting from 0).
+/// The key is empty for syntax errors.
+#[no_mangle]
+pub extern "C" fn winit_window_config_error_get_problem_key(
+    error: *mut ValueBox<WindowConfigError>,
+    index: usize,
+    key: *mut ValueBox<StringBox>,
+) {
+    with_problem(error, index, |problem| problem.key.clone())
+        .and_then(|problem_key| key.with_mut_ok(|key| key.set_string(problem_key)))
+        .log();
+}
+
+#[no_mangle]
+pub extern "C" fn winit_window_config_error_get_problem_message(
+    error: *mut ValueBox<WindowConfigError>,
+    index: usize,
+    message: *mut ValueBox<StringBox>,
+) {
+    with_problem(error, index, |problem| problem.message.clone())
+        .and_then(|problem_message| {
+            message.with_mut_ok(|message| message.set_string(problem_message))
+        })
+        .log();
+}
+
+#[no_mangle]
+pub extern "C" fn winit_window_config_error_drop(error: *mut ValueBox<WindowConfigError>) {
+    error.release();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn problems(config: &str, format: WinitConfigFormat) -> Vec<(String, WinitConfigProblemKind)> {
+        match WindowConfig::parse(config, format) {
+            Err(WinitError::InvalidWindowConfig(error)) => error
+                .problems()
+                .iter()
+                .map(|problem| (problem.key.clone(), problem.kind))
+                .collect(),
+            other => panic!("Expected an invalid configuration, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn unknown_keys() {
+        let problems = problems(
+            r#"{ "title": "Editor", "colour": "red", "x11": { "nmae": "editor" } }"#,
+            WinitConfigFormat::Json,
+        );
+        assert_eq!(
+            problems,
+            vec![
+                ("colour".to_string(), WinitConfigProblemKind::UnknownKey),
+                ("x11.nmae".to_string(), WinitConfigProblemKind::UnknownKey),
+            ]
+        );
+    }
+
+    #[test]
+    fn logical_and_physical_size() {
+        let problems = problems(
+            r#"
+            size = { width = 800, height = 600 }
+            physical_size = { width = 1600, height = 1200 }
+            "#,
+            WinitConfigFormat::Toml,
+        );
+        assert_eq!(
+            problems,
+            vec![(
+                "physical_size".to_string(),
+                WinitConfigProblemKind::InvalidValue
+            )]
+        );
+    }
+
+    #[test]
+    fn invalid_level() {
+        let problems = problems(r#"{ "level": "topmost" }"#, WinitConfigFormat::Json);
+        assert_eq!(
+            problems,
+            vec![("level".to_string(), WinitConfigProblemKind::InvalidValue)]
+        );
+    }
+
+    #[test]
+    fn syntax_error() {
+        let problems = problems("title = ", WinitConfigFormat::Toml);
+        assert_eq!(
+            problems,
+            vec![("".to_string(), WinitConfigProblemKind::Syntax)]
+        );
+    }
+
+    #[test]
+    fn value_round_trip() {
+        let config = WindowConfig {
+            title: Some("Editor".to_string()),
+            size: Some(LogicalSize::new(800.0, 600.0).into()),
+            position: Some(PhysicalPosition::new(-10, 20).into()),
+            min_size: Some(PhysicalSize::new(200, 100).into()),
+            centered: Some(true),
+            decorations: Some(false),
+            level: Some(WindowLevel::AlwaysOnTop),
+            icon: Some(PathBuf::from("icon.png")),
+            x11: X11Config {
+                name: Some(("Editor".to_string(), "editor".to_string())),
+                window_type: Some(WinitX11WindowType::Dialog),
+                ..Default::default()
+            },
+            macos: MacOSConfig {
+                full_size: Some(true),
+            },
+            ..Default::default()
+        };
+
+        let value = config.to_value();
+        let read_config = WindowConfig::from_value(&value).unwrap();
+        assert_eq!(read_config.to_value(), value);
+        assert_eq!(read_config.size, config.size);
+        assert_eq!(read_config.position, config.position);
+        assert_eq!(read_config.min_size, config.min_size);
+        assert_eq!(read_config.level, config.level);
+        assert_eq!(read_config.x11.window_type, config.x11.window_type);
+    }
+
+    #[test]
+    fn builder_round_trip() {
+        let icon = std::env::temp_dir().join(format!("winit-icon-{}.png", std::process::id()));
+        let mut encoder = png::Encoder::new(File::create(&icon).unwrap(), 2, 2);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(&[u8::MAX; 16]).unwrap();
+        writer.finish().unwrap();
+
+        let config = format!(
+            r#"
+            title = "Editor"
+            physical_size = {{ width = 800, height = 600 }}
+            position = {{ x = 10.5, y = 20.0 }}
+            min_size = {{ width = 200.0, height = 100.0 }}
+            physical_max_size = {{ width = 1600, height = 1200 }}
+            resize_increments = {{ width = 8.0, height = 16.0 }}
+            centered = true
+            fullscreen = true
+            decorations = false
+            transparent = true
+            resizable = false
+            maximized = true
+            visible = false
+            level = "always_on_top"
+            theme = "dark"
+            content_protected = true
+            active = false
+            icon = {:?}
+            ime_allowed = false
+            buttons = {{ close = true, minimize = false, maximize = true }}
+            parent = {{ window = 42, relation = "owned" }}
+
+            [x11]
+            name = {{ general = "Editor", instance = "editor" }}
+            window_type = "dialog"
+            override_redirect = true
+            base_size = {{ width = 10, height = 20 }}
+            parent_window = 4194305
+
+            [wayland]
+            name = {{ general = "Viewer", instance = "viewer" }}
+
+            [macos]
+            full_size = true
+            "#,
+            icon.to_string_lossy()
+        );
+
+        let parsed = WindowConfig::parse(&config, WinitConfigFormat::Toml).unwrap();
+        let expected = parsed.to_value();
+        let window_builder = parsed.into_builder().unwrap();
+        let serialized = WindowConfig::from_builder(&window_builder)
+            .serialize(WinitConfigFormat::Toml)
+            .unwrap();
+        std::fs::remove_file(&icon).unwrap();
+
+        let reparsed = WindowConfig::parse(&serialized, WinitConfigFormat::Toml).unwrap();
+        assert_eq!(reparsed.to_value(), expected);
+        assert_eq!(
+            expected.as_object().unwrap().len(),
+            WINDOW_KEYS.len() - 5,
+            "every setting except the alternative units is in the configuration"
+        );
+    }
+
+    #[test]
+    fn format_detection() {
+        assert_eq!(
+            detect_format(r#"  { "title": "Editor" }"#),
+            WinitConfigFormat::Json
+        );
+        assert_eq!(
+            detect_format("title = \"Editor\"\n[x11]\n"),
+            WinitConfigFormat::Toml
+        );
+        assert_eq!(detect_format(""), WinitConfigFormat::Toml);
+    }
+
+    #[test]
+    fn toml_and_json_are_equivalent() {
+        let json = WindowConfig::parse(
+            r#"{
+                "title": "Editor",
+                "size": { "width": 800, "height": 600 },
+                "physical_position": { "x": 10, "y": 20 },
+                "resizable": false,
+                "level": "always_on_bottom",
+                "wayland": { "name": { "general": "Editor", "instance": "editor" } }
+            }"#,
+            WinitConfigFormat::Json,
+        )
+        .unwrap();
+        let toml = WindowConfig::parse(
+            r#"
+            title = "Editor"
+            size = { width = 800, height = 600 }
+            physical_position = { x = 10, y = 20 }
+            resizable = false
+            level = "always_on_bottom"
+
+            [wayland]
+            name = { general = "Editor", instance = "editor" }
+            "#,
+            WinitConfigFormat::Toml,
+        )
+        .unwrap();
+        assert_eq!(json.to_value(), toml.to_value());
+
+        let serialized = toml.serialize(WinitConfigFormat::Toml).unwrap();
+        let reparsed = WindowConfig::parse(&serialized, WinitConfigFormat::Toml).unwrap();
+        assert_eq!(reparsed.to_value(), json.to_value());
+    }
+}
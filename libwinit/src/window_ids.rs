@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+
+use geometry_box::U128Box;
+use winit::window::WindowId;
+
+/// Maps winit window ids to the ids that are assigned by the library when a window is created.
+/// Assigned ids are monotonically increasing and are never reused, `0` means no window.
+#[derive(Debug)]
+pub struct WindowIds {
+    next_id: u64,
+    by_window_id: HashMap<WindowId, u64>,
+    by_id: HashMap<u64, WindowId>,
+}
+
+impl WindowIds {
+    pub fn new() -> Self {
+        Self {
+            next_id: 1,
+            by_window_id: Default::default(),
+            by_id: Default::default(),
+        }
+    }
+
+    /// Assign a new id to a window
+    pub fn register(&mut self, window_id: WindowId) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        // platforms may reuse the ids of destroyed windows
+        if let Some(previous_id) = self.by_window_id.insert(window_id, id) {
+            self.by_id.remove(&previous_id);
+        }
+        self.by_id.insert(id, window_id);
+        id
+    }
+
+    pub fn unregister(&mut self, window_id: &WindowId) -> Option<u64> {
+        self.by_window_id.remove(window_id).map(|id| {
+            self.by_id.remove(&id);
+            id
+        })
+    }
+
+    pub fn id(&self, window_id: &WindowId) -> Option<u64> {
+        self.by_window_id.get(window_id).copied()
+    }
+
+    pub fn window_id(&self, id: &U128Box) -> Option<WindowId> {
+        if id.high != 0 {
+            return None;
+        }
+        self.by_id.get(&id.low).copied()
+    }
+}
+
+impl Default for WindowIds {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PollingEventLoop;
+
+    fn window_id(raw_id: u64) -> WindowId {
+        WindowId::from(raw_id)
+    }
+
+    #[test]
+    fn ids_start_at_one_and_increase() {
+        let mut window_ids = WindowIds::new();
+        assert_eq!(window_ids.register(window_id(10)), 1);
+        assert_eq!(window_ids.register(window_id(20)), 2);
+        assert_eq!(window_ids.register(window_id(30)), 3);
+    }
+
+    #[test]
+    fn ids_are_not_reused() {
+        let mut window_ids = WindowIds::new();
+        assert_eq!(window_ids.register(window_id(10)), 1);
+        window_ids.unregister(&window_id(10));
+        assert_eq!(window_ids.register(window_id(10)), 2);
+        assert_eq!(window_ids.window_id(&U128Box::from(1)), None);
+        assert_eq!(window_ids.window_id(&U128Box::from(2)), Some(window_id(10)));
+    }
+
+    #[test]
+    fn ids_map_in_both_directions() {
+        let mut window_ids = WindowIds::new();
+        let id = window_ids.register(window_id(10));
+        assert_eq!(window_ids.id(&window_id(10)), Some(id));
+        assert_eq!(
+            window_ids.window_id(&U128Box::from(id as u128)),
+            Some(window_id(10))
+        );
+    }
+
+    #[test]
+    fn unregister_removes_both_directions_once() {
+        let mut window_ids = WindowIds::new();
+        let id = window_ids.register(window_id(10));
+        assert_eq!(window_ids.unregister(&window_id(10)), Some(id));
+        assert_eq!(window_ids.id(&window_id(10)), None);
+        assert_eq!(window_ids.window_id(&U128Box::from(id as u128)), None);
+        assert_eq!(window_ids.unregister(&window_id(10)), None);
+    }
+
+    #[test]
+    fn ids_with_high_word_are_rejected() {
+        let mut window_ids = WindowIds::new();
+        let id = window_ids.register(window_id(10));
+        let mut host_id = U128Box::from(id as u128);
+        host_id.high = 1;
+        assert_eq!(window_ids.window_id(&host_id), None);
+    }
+
+    #[test]
+    fn unknown_ids_map_to_zero() {
+        let window_ids = WindowIds::new();
+        assert_eq!(window_ids.id(&window_id(10)), None);
+        assert_eq!(window_ids.window_id(&U128Box::from(0)), None);
+        assert_eq!(window_ids.window_id(&U128Box::from(1)), None);
+
+        let event_loop = PollingEventLoop::new();
+        assert_eq!(event_loop.host_window_id(&window_id(10)).get(), 0);
+    }
+}
@@ -1,19 +1,25 @@
 use std::sync::{Arc, Mutex};
 
 use core::default::Default;
-use winit::dpi::{PhysicalPosition, PhysicalSize};
-use winit::window::WindowId;
+use winit::dpi::{PhysicalPosition, PhysicalSize, Position, Size};
+use winit::window::{
+    CursorGrabMode, CursorIcon, Fullscreen, Theme, WindowButtons, WindowId, WindowLevel,
+};
+
+use crate::{CustomCursor, FrameInsets, WindowCommand};
 
 #[derive(Debug, Clone)]
 pub struct WindowRef {
     id: WindowId,
+    host_id: u64,
     data: Arc<Mutex<WindowData>>,
 }
 
 impl WindowRef {
-    pub fn new(id: &WindowId) -> Self {
+    pub fn new(id: &WindowId, host_id: u64) -> Self {
         Self {
             id: id.clone(),
+            host_id,
             data: Default::default(),
         }
     }
@@ -23,52 +29,458 @@ impl WindowRef {
         self.id
     }
 
-    pub fn scale_factor(&self) -> crate::Result<f64> {
+    /// Return an id that is assigned to the window by the library and is sent to the host
+    /// as part of events. Can be called from any thread
+    pub fn host_id(&self) -> u64 {
+        self.host_id
+    }
+
+    fn read<T>(&self, reader: impl FnOnce(&WindowData) -> T) -> crate::Result<T> {
         self.data
             .lock()
             .map_err(|error| error.into())
-            .map(|lock| lock.scale_factor)
+            .map(|lock| reader(&lock))
     }
 
-    pub fn outer_position(&self) -> crate::Result<PhysicalPosition<i32>> {
+    fn write(&self, writer: impl FnOnce(&mut WindowData)) -> crate::Result<()> {
         self.data
             .lock()
             .map_err(|error| error.into())
-            .map(|lock| lock.outer_position.clone())
+            .map(|mut lock| writer(&mut lock))
+    }
+
+    pub fn scale_factor(&self) -> crate::Result<f64> {
+        self.read(|data| data.scale_factor)
+    }
+
+    pub fn outer_position(&self) -> crate::Result<PhysicalPosition<i32>> {
+        self.read(|data| data.outer_position)
     }
 
     pub fn inner_size(&self) -> crate::Result<PhysicalSize<u32>> {
-        self.data
-            .lock()
-            .map_err(|error| error.into())
-            .map(|lock| lock.inner_size.clone())
+        self.read(|data| data.inner_size)
+    }
+
+    pub fn outer_size(&self) -> crate::Result<PhysicalSize<u32>> {
+        self.read(|data| data.outer_size)
+    }
+
+    /// The position of the client area. Equals to the outer position on platforms
+    /// that can't tell it
+    pub fn inner_position(&self) -> crate::Result<PhysicalPosition<i32>> {
+        self.read(|data| data.inner_position)
+    }
+
+    pub fn frame_insets(&self) -> crate::Result<FrameInsets<i32>> {
+        self.read(|data| {
+            FrameInsets::between(
+                data.outer_position,
+                data.outer_size,
+                data.inner_position,
+                data.inner_size,
+            )
+        })
+    }
+
+    pub fn is_focused(&self) -> crate::Result<bool> {
+        self.read(|data| data.is_focused)
+    }
+
+    /// Return true if the cursor is inside of the window
+    pub fn is_hovered(&self) -> crate::Result<bool> {
+        self.read(|data| data.is_hovered)
+    }
+
+    pub fn is_minimized(&self) -> crate::Result<bool> {
+        self.read(|data| data.is_minimized)
+    }
+
+    pub fn is_maximized(&self) -> crate::Result<bool> {
+        self.read(|data| data.is_maximized)
+    }
+
+    pub fn is_visible(&self) -> crate::Result<bool> {
+        self.read(|data| data.is_visible)
+    }
+
+    pub fn is_occluded(&self) -> crate::Result<bool> {
+        self.read(|data| data.is_occluded)
+    }
+
+    pub fn fullscreen(&self) -> crate::Result<Option<Fullscreen>> {
+        self.read(|data| data.fullscreen.clone())
+    }
+
+    pub fn is_decorated(&self) -> crate::Result<bool> {
+        self.read(|data| data.is_decorated)
+    }
+
+    pub fn is_resizable(&self) -> crate::Result<bool> {
+        self.read(|data| data.is_resizable)
+    }
+
+    /// winit can't query the transparency, so it is the transparency that was last requested
+    pub fn is_transparent(&self) -> crate::Result<bool> {
+        self.read(|data| data.is_transparent)
+    }
+
+    /// winit can't query the window level, so it is the level that was last requested
+    pub fn window_level(&self) -> crate::Result<WindowLevel> {
+        self.read(|data| data.window_level)
+    }
+
+    pub fn enabled_buttons(&self) -> crate::Result<WindowButtons> {
+        self.read(|data| data.enabled_buttons)
+    }
+
+    pub fn theme(&self) -> crate::Result<Option<Theme>> {
+        self.read(|data| data.theme)
+    }
+
+    pub fn title(&self) -> crate::Result<String> {
+        self.read(|data| data.title.clone())
+    }
+
+    /// The requested cursor grab mode. It is released while the window is not focused
+    /// and restored when the window gains focus
+    pub fn cursor_grab(&self) -> crate::Result<CursorGrabMode> {
+        self.read(|data| data.cursor_grab)
+    }
+
+    pub fn is_cursor_visible(&self) -> crate::Result<bool> {
+        self.read(|data| data.is_cursor_visible)
+    }
+
+    /// Width divided by height that the library keeps when the window is resized
+    pub fn aspect_ratio(&self) -> crate::Result<Option<f64>> {
+        self.read(|data| data.aspect_ratio)
+    }
+
+    pub fn is_ime_allowed(&self) -> crate::Result<bool> {
+        self.read(|data| data.is_ime_allowed)
+    }
+
+    /// The last requested area of the text caret, to be given to the input method
+    /// as a whole once winit supports it
+    pub fn ime_cursor_area(&self) -> crate::Result<Option<(Position, Size)>> {
+        self.read(|data| data.ime_cursor_area)
+    }
+
+    /// The custom cursor shown over the window, which is set again when the scale factor
+    /// changes to pick the matching image
+    pub fn custom_cursor(&self) -> crate::Result<Option<CustomCursor>> {
+        self.read(|data| data.custom_cursor.clone())
+    }
+
+    /// The last known position of the cursor relative to the window
+    pub fn cursor_position(&self) -> crate::Result<Option<PhysicalPosition<f64>>> {
+        self.read(|data| data.cursor_position)
     }
 
     pub fn set_inner_size(&self, size: PhysicalSize<u32>) -> crate::Result<()> {
-        self.data
-            .lock()
-            .map_err(|error| error.into())
-            .map(|mut lock| lock.inner_size = size)
+        self.write(|data| data.inner_size = size)
+    }
+
+    pub fn set_outer_size(&self, size: PhysicalSize<u32>) -> crate::Result<()> {
+        self.write(|data| data.outer_size = size)
+    }
+
+    pub fn set_inner_position(&self, position: PhysicalPosition<i32>) -> crate::Result<()> {
+        self.write(|data| data.inner_position = position)
     }
 
     pub fn set_scale_factor(&self, scale_factor: f64) -> crate::Result<()> {
-        self.data
-            .lock()
-            .map_err(|error| error.into())
-            .map(|mut lock| lock.scale_factor = scale_factor)
+        self.write(|data| data.scale_factor = scale_factor)
     }
 
     pub fn set_outer_position(&self, position: PhysicalPosition<i32>) -> crate::Result<()> {
-        self.data
-            .lock()
-            .map_err(|error| error.into())
-            .map(|mut lock| lock.outer_position = position)
+        self.write(|data| data.outer_position = position)
+    }
+
+    pub fn set_focused(&self, is_focused: bool) -> crate::Result<()> {
+        self.write(|data| data.is_focused = is_focused)
+    }
+
+    pub fn set_hovered(&self, is_hovered: bool) -> crate::Result<()> {
+        self.write(|data| data.is_hovered = is_hovered)
+    }
+
+    pub fn set_minimized(&self, is_minimized: bool) -> crate::Result<()> {
+        self.write(|data| data.is_minimized = is_minimized)
+    }
+
+    pub fn set_maximized(&self, is_maximized: bool) -> crate::Result<()> {
+        self.write(|data| data.is_maximized = is_maximized)
+    }
+
+    pub fn set_visible(&self, is_visible: bool) -> crate::Result<()> {
+        self.write(|data| data.is_visible = is_visible)
+    }
+
+    pub fn set_occluded(&self, is_occluded: bool) -> crate::Result<()> {
+        self.write(|data| data.is_occluded = is_occluded)
+    }
+
+    pub fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) -> crate::Result<()> {
+        self.write(|data| data.fullscreen = fullscreen)
+    }
+
+    pub fn set_decorated(&self, is_decorated: bool) -> crate::Result<()> {
+        self.write(|data| data.is_decorated = is_decorated)
+    }
+
+    pub fn set_resizable(&self, is_resizable: bool) -> crate::Result<()> {
+        self.write(|data| data.is_resizable = is_resizable)
+    }
+
+    pub fn set_transparent(&self, is_transparent: bool) -> crate::Result<()> {
+        self.write(|data| data.is_transparent = is_transparent)
+    }
+
+    pub fn set_window_level(&self, window_level: WindowLevel) -> crate::Result<()> {
+        self.write(|data| data.window_level = window_level)
+    }
+
+    pub fn set_enabled_buttons(&self, enabled_buttons: WindowButtons) -> crate::Result<()> {
+        self.write(|data| data.enabled_buttons = enabled_buttons)
+    }
+
+    pub fn set_theme(&self, theme: Option<Theme>) -> crate::Result<()> {
+        self.write(|data| data.theme = theme)
+    }
+
+    pub fn set_title(&self, title: impl Into<String>) -> crate::Result<()> {
+        let title = title.into();
+        self.write(|data| data.title = title)
+    }
+
+    pub fn set_cursor_grab(&self, cursor_grab: CursorGrabMode) -> crate::Result<()> {
+        self.write(|data| data.cursor_grab = cursor_grab)
+    }
+
+    pub fn set_cursor_visible(&self, is_cursor_visible: bool) -> crate::Result<()> {
+        self.write(|data| data.is_cursor_visible = is_cursor_visible)
+    }
+
+    pub fn set_aspect_ratio(&self, aspect_ratio: Option<f64>) -> crate::Result<()> {
+        self.write(|data| data.aspect_ratio = aspect_ratio)
+    }
+
+    pub fn set_ime_allowed(&self, is_ime_allowed: bool) -> crate::Result<()> {
+        self.write(|data| data.is_ime_allowed = is_ime_allowed)
+    }
+
+    pub fn set_ime_cursor_area(&self, position: Position, size: Size) -> crate::Result<()> {
+        self.write(|data| data.ime_cursor_area = Some((position, size)))
+    }
+
+    pub fn set_custom_cursor(&self, custom_cursor: Option<CustomCursor>) -> crate::Result<()> {
+        self.write(|data| data.custom_cursor = custom_cursor)
+    }
+
+    pub fn set_cursor_position(
+        &self,
+        position: Option<PhysicalPosition<f64>>,
+    ) -> crate::Result<()> {
+        self.write(|data| data.cursor_position = position)
+    }
+
+    /// Optimistically update the cached state before the command is applied to the window
+    pub fn apply_command(&self, command: &WindowCommand) -> crate::Result<()> {
+        match command {
+            WindowCommand::SetTitle(title) => self.set_title(title.as_str()),
+            WindowCommand::SetInnerSize(size) => self.set_inner_size(*size),
+            WindowCommand::SetOuterPosition(position) => self.set_outer_position(*position),
+            WindowCommand::SetMaximized(maximized) => self.set_maximized(*maximized),
+            WindowCommand::SetMinimized(minimized) => self.set_minimized(*minimized),
+            WindowCommand::SetVisible(visible) => self.set_visible(*visible),
+            WindowCommand::SetFullscreen(fullscreen) => self.set_fullscreen(fullscreen.clone()),
+            WindowCommand::SetDecorations(decorations) => self.set_decorated(*decorations),
+            WindowCommand::SetResizable(resizable) => self.set_resizable(*resizable),
+            WindowCommand::SetTransparent(transparent) => self.set_transparent(*transparent),
+            WindowCommand::SetWindowLevel(level) => self.set_window_level(*level),
+            WindowCommand::SetEnabledButtons(buttons) => self.set_enabled_buttons(*buttons),
+            WindowCommand::SetCursorGrab(mode) => self.set_cursor_grab(*mode),
+            WindowCommand::SetCursorVisible(visible) => self.set_cursor_visible(*visible),
+            WindowCommand::SetImeAllowed(allowed) => self.set_ime_allowed(*allowed),
+            WindowCommand::SetAspectRatio(aspect_ratio) => self.set_aspect_ratio(*aspect_ratio),
+            WindowCommand::SetImeCursorArea(position, size) => {
+                self.set_ime_cursor_area(*position, *size)
+            }
+            WindowCommand::SetCursorIcon(_) => self.set_custom_cursor(None),
+            WindowCommand::SetCustomCursor(cursor) => self.set_custom_cursor(Some(cursor.clone())),
+            WindowCommand::SetWindowIcon(_)
+            | WindowCommand::SetMinInnerSize(_)
+            | WindowCommand::SetMaxInnerSize(_)
+            | WindowCommand::SetResizeIncrements(_)
+            | WindowCommand::SetCursorPosition(_)
+            | WindowCommand::RequestUserAttention(_)
+            | WindowCommand::DragWindow
+            | WindowCommand::DragResizeWindow(_)
+            | WindowCommand::SetCursorHittest(_)
+            | WindowCommand::SetInputShape(_)
+            | WindowCommand::Focus
+            | WindowCommand::RequestRedraw
+            | WindowCommand::Destroy => Ok(()),
+        }
+    }
+
+    /// Return a command that restores the cached state changed by a given command,
+    /// or `None` if the command does not change the cached state
+    pub fn restore_command(&self, command: &WindowCommand) -> crate::Result<Option<WindowCommand>> {
+        Ok(Some(match command {
+            WindowCommand::SetTitle(_) => WindowCommand::SetTitle(self.title()?),
+            WindowCommand::SetInnerSize(_) => WindowCommand::SetInnerSize(self.inner_size()?),
+            WindowCommand::SetOuterPosition(_) => {
+                WindowCommand::SetOuterPosition(self.outer_position()?)
+            }
+            WindowCommand::SetMaximized(_) => WindowCommand::SetMaximized(self.is_maximized()?),
+            WindowCommand::SetMinimized(_) => WindowCommand::SetMinimized(self.is_minimized()?),
+            WindowCommand::SetVisible(_) => WindowCommand::SetVisible(self.is_visible()?),
+            WindowCommand::SetFullscreen(_) => WindowCommand::SetFullscreen(self.fullscreen()?),
+            WindowCommand::SetDecorations(_) => WindowCommand::SetDecorations(self.is_decorated()?),
+            WindowCommand::SetResizable(_) => WindowCommand::SetResizable(self.is_resizable()?),
+            WindowCommand::SetTransparent(_) => {
+                WindowCommand::SetTransparent(self.is_transparent()?)
+            }
+            WindowCommand::SetWindowLevel(_) => WindowCommand::SetWindowLevel(self.window_level()?),
+            WindowCommand::SetEnabledButtons(_) => {
+                WindowCommand::SetEnabledButtons(self.enabled_buttons()?)
+            }
+            WindowCommand::SetCursorGrab(_) => WindowCommand::SetCursorGrab(self.cursor_grab()?),
+            WindowCommand::SetCursorVisible(_) => {
+                WindowCommand::SetCursorVisible(self.is_cursor_visible()?)
+            }
+            WindowCommand::SetImeAllowed(_) => WindowCommand::SetImeAllowed(self.is_ime_allowed()?),
+            WindowCommand::SetAspectRatio(_) => WindowCommand::SetAspectRatio(self.aspect_ratio()?),
+            WindowCommand::SetImeCursorArea(_, _) => match self.ime_cursor_area()? {
+                Some((position, size)) => WindowCommand::SetImeCursorArea(position, size),
+                None => return Ok(None),
+            },
+            // the cursor icon is not cached, any icon clears the custom cursor from the cache
+            WindowCommand::SetCursorIcon(_) | WindowCommand::SetCustomCursor(_) => {
+                match self.custom_cursor()? {
+                    Some(cursor) => WindowCommand::SetCustomCursor(cursor),
+                    None => WindowCommand::SetCursorIcon(CursorIcon::Default),
+                }
+            }
+            WindowCommand::SetWindowIcon(_)
+            | WindowCommand::SetMinInnerSize(_)
+            | WindowCommand::SetMaxInnerSize(_)
+            | WindowCommand::SetResizeIncrements(_)
+            | WindowCommand::SetCursorPosition(_)
+            | WindowCommand::RequestUserAttention(_)
+            | WindowCommand::DragWindow
+            | WindowCommand::DragResizeWindow(_)
+            | WindowCommand::SetCursorHittest(_)
+            | WindowCommand::SetInputShape(_)
+            | WindowCommand::Focus
+            | WindowCommand::RequestRedraw
+            | WindowCommand::Destroy => return Ok(None),
+        }))
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 struct WindowData {
     outer_position: PhysicalPosition<i32>,
+    outer_size: PhysicalSize<u32>,
+    inner_position: PhysicalPosition<i32>,
     inner_size: PhysicalSize<u32>,
     scale_factor: f64,
+    is_focused: bool,
+    is_hovered: bool,
+    is_minimized: bool,
+    is_maximized: bool,
+    is_visible: bool,
+    is_occluded: bool,
+    fullscreen: Option<Fullscreen>,
+    is_decorated: bool,
+    is_resizable: bool,
+    is_transparent: bool,
+    window_level: WindowLevel,
+    enabled_buttons: WindowButtons,
+    theme: Option<Theme>,
+    title: String,
+    cursor_grab: CursorGrabMode,
+    is_cursor_visible: bool,
+    is_ime_allowed: bool,
+    ime_cursor_area: Option<(Position, Size)>,
+    custom_cursor: Option<CustomCursor>,
+    aspect_ratio: Option<f64>,
+    cursor_position: Option<PhysicalPosition<f64>>,
+}
+
+impl Default for WindowData {
+    fn default() -> Self {
+        Self {
+            outer_position: Default::default(),
+            outer_size: Default::default(),
+            inner_position: Default::default(),
+            inner_size: Default::default(),
+            scale_factor: Default::default(),
+            is_focused: false,
+            is_hovered: false,
+            is_minimized: false,
+            is_maximized: false,
+            is_visible: false,
+            is_occluded: false,
+            fullscreen: None,
+            is_decorated: true,
+            is_resizable: true,
+            is_transparent: false,
+            window_level: Default::default(),
+            enabled_buttons: WindowButtons::all(),
+            theme: None,
+            title: Default::default(),
+            cursor_grab: CursorGrabMode::None,
+            is_cursor_visible: true,
+            is_ime_allowed: false,
+            ime_cursor_area: None,
+            custom_cursor: None,
+            aspect_ratio: None,
+            cursor_position: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restore_command_reverts_the_cached_state() {
+        let window_ref = WindowRef::new(&WindowId::from(1), 1);
+        let command = WindowCommand::SetResizable(false);
+
+        let restore_command = window_ref.restore_command(&command).unwrap().unwrap();
+        window_ref.apply_command(&command).unwrap();
+        assert!(!window_ref.is_resizable().unwrap());
+
+        window_ref.apply_command(&restore_command).unwrap();
+        assert!(window_ref.is_resizable().unwrap());
+    }
+
+    #[test]
+    fn restore_command_reverts_the_custom_cursor() {
+        let window_ref = WindowRef::new(&WindowId::from(1), 1);
+        let image = crate::CursorImage::from_rgba(vec![0; 4], 1, 1, 0, 0).unwrap();
+        let command = WindowCommand::SetCustomCursor(CustomCursor::new(image));
+
+        let restore_command = window_ref.restore_command(&command).unwrap().unwrap();
+        window_ref.apply_command(&command).unwrap();
+        assert!(window_ref.custom_cursor().unwrap().is_some());
+
+        window_ref.apply_command(&restore_command).unwrap();
+        assert!(window_ref.custom_cursor().unwrap().is_none());
+    }
+
+    #[test]
+    fn commands_without_cached_state_have_no_restore_command() {
+        let window_ref = WindowRef::new(&WindowId::from(1), 1);
+        assert!(window_ref
+            .restore_command(&WindowCommand::Focus)
+            .unwrap()
+            .is_none());
+    }
 }
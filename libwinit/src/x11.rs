@@ -0,0 +1,215 @@
+//! Xlib extensions that winit 0.28 does not expose, loaded lazily at runtime
+use std::ffi::{c_int, c_ulong, c_void};
+use std::sync::OnceLock;
+
+use parking_lot::{const_mutex, Mutex};
+use winit::dpi::{PhysicalPosition, PhysicalSize};
+use winit::platform::x11::WindowExtX11;
+use winit::window::Window;
+use x11_dl::xcursor::Xcursor;
+use x11_dl::xfixes::Xlib as XFixes;
+use x11_dl::xlib::{Display, XRectangle, Xlib};
+
+use crate::{Result, WinitError};
+
+/// Shape kind of the input region from `X11/extensions/shape.h`
+const SHAPE_INPUT: c_int = 2;
+
+pub(crate) fn xlib() -> Result<&'static Xlib> {
+    static XLIB: OnceLock<Option<Xlib>> = OnceLock::new();
+    XLIB.get_or_init(|| Xlib::open().ok())
+        .as_ref()
+        .ok_or(WinitError::X11LibraryNotAvailable("Xlib"))
+}
+
+pub(crate) fn xcursor() -> Result<&'static Xcursor> {
+    static XCURSOR: OnceLock<Option<Xcursor>> = OnceLock::new();
+    XCURSOR
+        .get_or_init(|| Xcursor::open().ok())
+        .as_ref()
+        .ok_or(WinitError::X11LibraryNotAvailable("Xcursor"))
+}
+
+pub(crate) fn xfixes() -> Result<&'static XFixes> {
+    static XFIXES: OnceLock<Option<XFixes>> = OnceLock::new();
+    XFIXES
+        .get_or_init(|| XFixes::open().ok())
+        .as_ref()
+        .ok_or(WinitError::X11LibraryNotAvailable("XFixes"))
+}
+
+/// Displays together with the number of their windows that are alive.
+/// A window keeps its display open, once the last one is gone the display may be closed
+static DISPLAY_WINDOWS: Mutex<Vec<(usize, usize)>> = const_mutex(Vec::new());
+
+/// Remember that the display of the window is open until the window is released
+pub(crate) fn retain_display(window: &Window) {
+    if let Some((display, _)) = xlib_window(window) {
+        let mut display_windows = DISPLAY_WINDOWS.lock();
+        match display_windows
+            .iter_mut()
+            .find(|(each_display, _)| *each_display == display as usize)
+        {
+            Some((_, count)) => *count += 1,
+            None => display_windows.push((display as usize, 1)),
+        }
+    }
+}
+
+/// Must be called before the window is dropped
+pub(crate) fn release_display(window: &Window) {
+    if let Some((display, _)) = xlib_window(window) {
+        let mut display_windows = DISPLAY_WINDOWS.lock();
+        if let Some((_, count)) = display_windows
+            .iter_mut()
+            .find(|(each_display, _)| *each_display == display as usize)
+        {
+            *count -= 1;
+        }
+        display_windows.retain(|(_, count)| *count > 0);
+    }
+}
+
+/// Run the callback only if the display is kept open by one of its windows.
+/// No window can be released while the callback runs
+pub(crate) fn with_open_display(display: *mut Display, callback: impl FnOnce(*mut Display)) {
+    let display_windows = DISPLAY_WINDOWS.lock();
+    if display_windows
+        .iter()
+        .any(|(each_display, _)| *each_display == display as usize)
+    {
+        callback(display);
+    }
+}
+
+/// Return the X11 display and window of a given window or None on other backends
+pub(crate) fn xlib_window(window: &Window) -> Option<(*mut Display, c_ulong)> {
+    window
+        .xlib_display()
+        .zip(window.xlib_window())
+        .map(|(display, xlib_window)| (display as *mut Display, xlib_window))
+}
+
+/// Only the given rectangles of the window receive mouse input, the rest lets clicks pass
+/// through to the windows below. `None` restores the default input shape.
+pub(crate) fn set_input_shape(
+    window: &Window,
+    shape: Option<&[(PhysicalPosition<i32>, PhysicalSize<u32>)]>,
+) -> Result<()> {
+    let (display, xlib_window) = xlib_window(window).ok_or(WinitError::NotX11Window)?;
+    let xfixes = xfixes()?;
+    let xlib = xlib()?;
+
+    unsafe {
+        match shape {
+            None => (xfixes.XFixesSetWindowShapeRegion)(display, xlib_window, SHAPE_INPUT, 0, 0, 0),
+            Some(shape) => {
+                let mut rectangles = shape
+                    .iter()
+                    .map(|(position, size)| XRectangle {
+                        x: position.x.clamp(i16::MIN as i32, i16::MAX as i32) as i16,
+                        y: position.y.clamp(i16::MIN as i32, i16::MAX as i32) as i16,
+                        width: size.width.min(u16::MAX as u32) as u16,
+                        height: size.height.min(u16::MAX as u32) as u16,
+                    })
+                    .collect::<Vec<_>>();
+                let region = (xfixes.XFixesCreateRegion)(
+                    display,
+                    rectangles.as_mut_ptr(),
+                    rectangles.len() as c_int,
+                );
+                (xfixes.XFixesSetWindowShapeRegion)(
+                    display,
+                    xlib_window,
+                    SHAPE_INPUT,
+                    0,
+                    0,
+                    region,
+                );
+                (xfixes.XFixesDestroyRegion)(display, region);
+            }
+        }
+        (xlib.XFlush)(display);
+    }
+    Ok(())
+}
+
+/// Embed the window into a window of another application.
+/// The window must not be mapped yet, otherwise the window manager already framed it
+pub(crate) fn embed_into(window: &Window, parent_window: c_ulong) -> Result<()> {
+    let (display, xlib_window) = xlib_window(window).ok_or(WinitError::NotX11Window)?;
+    let xlib = xlib()?;
+
+    unsafe {
+        (xlib.XReparentWindow)(display, xlib_window, parent_window, 0, 0);
+        (xlib.XFlush)(display);
+    }
+    Ok(())
+}
+
+/// Return true if the urgency hint is set on the window
+pub(crate) fn is_urgent(window: &Window) -> Result<bool> {
+    let (display, xlib_window) = xlib_window(window).ok_or(WinitError::NotX11Window)?;
+    let xlib = xlib()?;
+
+    unsafe {
+        let hints = (xlib.XGetWMHints)(display, xlib_window);
+        if hints.is_null() {
+            return Ok(false);
+        }
+        let is_urgent = (*hints).flags & x11_dl::xlib::XUrgencyHint != 0;
+        (xlib.XFree)(hints as *mut c_void);
+        Ok(is_urgent)
+    }
+}
+
+/// Let the window manager keep the window above its owner and minimize them together.
+/// Window managers read the hint when the window is mapped, so it must be set before that
+pub(crate) fn set_transient_for(window: &Window, owner_window: c_ulong) -> Result<()> {
+    let (display, xlib_window) = xlib_window(window).ok_or(WinitError::NotX11Window)?;
+    let xlib = xlib()?;
+
+    unsafe {
+        (xlib.XSetTransientForHint)(display, xlib_window, owner_window);
+        (xlib.XFlush)(display);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use winit::event_loop::EventLoopBuilder;
+    use winit::platform::x11::EventLoopBuilderExtX11;
+    use winit::window::{UserAttentionType, WindowBuilder};
+
+    use super::*;
+    use crate::WindowCommand;
+
+    /// Run under a local X server, for example `Xvfb :99 &` followed by
+    /// `DISPLAY=:99 cargo test -- --ignored`
+    #[test]
+    #[ignore = "needs an X server"]
+    fn urgency_hint_follows_attention_requests() {
+        if std::env::var_os("DISPLAY").is_none() {
+            eprintln!("DISPLAY is not set, skipping");
+            return;
+        }
+
+        let event_loop = EventLoopBuilder::new()
+            .with_x11()
+            .with_any_thread(true)
+            .build();
+        let window = WindowBuilder::new().build(&event_loop).unwrap();
+        assert!(!is_urgent(&window).unwrap());
+
+        WindowCommand::RequestUserAttention(Some(UserAttentionType::Critical))
+            .apply(&window)
+            .unwrap();
+        assert!(is_urgent(&window).unwrap());
+
+        WindowCommand::RequestUserAttention(None)
+            .apply(&window)
+            .unwrap();
+        assert!(!is_urgent(&window).unwrap());
+    }
+}